@@ -5,3 +5,6 @@ pub const REWARD_VAULT_SEED: &[u8] = b"reward";
 
 #[constant]
 pub const STAKE_INFO_SEED: &[u8] = b"stake_info";
+
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
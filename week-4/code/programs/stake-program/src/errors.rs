@@ -9,5 +9,14 @@ pub enum AppError {
     NotStaked,
 
     #[msg("No tokens to stake")]
-    NoToken
-}
\ No newline at end of file
+    NoToken,
+
+    #[msg("Program is paused")]
+    Paused,
+
+    #[msg("Program is not paused")]
+    NotPaused,
+
+    #[msg("Signer is not the admin")]
+    Unauthorized,
+}
@@ -0,0 +1,26 @@
+use crate::contants::CONFIG_SEED;
+use crate::errors::AppError;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = pending_admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.admin = config.pending_admin;
+    config.pending_admin = Pubkey::default();
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::contants::{CONFIG_SEED, STAKE_INFO_SEED};
+use crate::errors::AppError;
+use crate::instructions::harvest_withheld_fees;
+use crate::state::{Config, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // a missing position fails here with AccountNotInitialized
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, staker.key().as_ref()],
+        bump,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stake_info,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = staker,
+        associated_token::token_program = token_program,
+    )]
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// return principal without rewards, only available while the program is paused
+pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+    if !ctx.accounts.config.paused {
+        return Err(AppError::NotPaused.into());
+    }
+
+    if ctx.accounts.stake_info.amount == 0 {
+        return Err(AppError::NotStaked.into());
+    }
+
+    let stake_amount = ctx.accounts.stake_info.amount;
+
+    // transfer token from vault back to staker
+    let stake_info_bump = ctx.bumps.stake_info;
+    let staker_key = ctx.accounts.staker.key();
+    let stake_info_signer_seeds: &[&[&[u8]]] =
+        &[&[STAKE_INFO_SEED, staker_key.as_ref(), &[stake_info_bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.staker_token_account.to_account_info(),
+                authority: ctx.accounts.stake_info.to_account_info(),
+            },
+            stake_info_signer_seeds,
+        ),
+        stake_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // a vault holding withheld transfer fees cannot be closed, move them to the mint first
+    harvest_withheld_fees(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.vault_token_account.to_account_info(),
+    )?;
+
+    // close vault token account and return rent to staker
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.staker.to_account_info(),
+            authority: ctx.accounts.stake_info.to_account_info(),
+        },
+        stake_info_signer_seeds,
+    ))?;
+
+    // close stake_info and return rent to staker
+    ctx.accounts
+        .stake_info
+        .close(ctx.accounts.staker.to_account_info())?;
+
+    Ok(())
+}
@@ -1,13 +1,37 @@
-use crate::contants::REWARD_VAULT_SEED;
+use crate::contants::{CONFIG_SEED, REWARD_VAULT_SEED};
+use crate::errors::AppError;
+use crate::state::Config;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    // only the upgrade authority of the program can create the config
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ AppError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_SEED],
+        bump,
+        space = 8 + Config::INIT_SPACE
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
@@ -16,13 +40,20 @@ pub struct Initialize<'info> {
         bump,
         token::mint = mint,
         token::authority = reward_vault,
+        token::token_program = token_program,
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn initialize(_ctx: Context<Initialize>) -> Result<()> {
+pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = Pubkey::default();
+    config.paused = false;
+
     Ok(())
 }
@@ -1,7 +1,17 @@
+pub mod accept_admin;
+pub mod admin_withdraw;
+pub mod emergency_unstake;
 pub mod initialize;
+pub mod propose_admin;
+pub mod set_paused;
 pub mod stake;
 pub mod unstake;
 
+pub use accept_admin::*;
+pub use admin_withdraw::*;
+pub use emergency_unstake::*;
 pub use initialize::*;
+pub use propose_admin::*;
+pub use set_paused::*;
 pub use stake::*;
-pub use unstake::*;
\ No newline at end of file
+pub use unstake::*;
@@ -0,0 +1,24 @@
+use crate::contants::CONFIG_SEED;
+use crate::errors::AppError;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// the current admin keeps its rights until the new admin accepts
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.config.pending_admin = new_admin;
+
+    Ok(())
+}
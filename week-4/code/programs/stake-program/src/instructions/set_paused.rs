@@ -0,0 +1,25 @@
+use crate::contants::CONFIG_SEED;
+use crate::errors::AppError;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+
+    msg!("paused: {}", paused);
+
+    Ok(())
+}
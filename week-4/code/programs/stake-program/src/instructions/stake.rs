@@ -1,11 +1,10 @@
-use crate::contants::STAKE_INFO_SEED;
+use crate::contants::{CONFIG_SEED, STAKE_INFO_SEED};
 use crate::errors::AppError;
-use crate::state::StakeInfo;
+use crate::state::{Config, StakeInfo};
 use anchor_lang::prelude::*;
-use anchor_spl::token::Transfer;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -13,7 +12,16 @@ pub struct Stake<'info> {
     #[account(mut)]
     pub staker: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
@@ -29,52 +37,74 @@ pub struct Stake<'info> {
         payer = staker,
         associated_token::mint = mint,
         associated_token::authority = stake_info,
+        associated_token::token_program = token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = staker,
+        associated_token::token_program = token_program,
     )]
-    pub staker_token_account: Account<'info, TokenAccount>,
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-    let stake_info = &mut ctx.accounts.stake_info;
+    if ctx.accounts.config.paused {
+        return Err(AppError::Paused.into());
+    }
 
-    if stake_info.is_staked {
+    if ctx.accounts.stake_info.amount > 0 {
         return Err(AppError::IsStaked.into());
     }
 
-    if amount <= 0 {
+    if amount == 0 {
         return Err(AppError::NoToken.into());
     }
 
     let clock = Clock::get()?;
 
-    stake_info.staker = ctx.accounts.staker.key();
-    stake_info.mint = ctx.accounts.mint.key();
-    stake_info.stake_at = clock.slot;
-    stake_info.is_staked = true;
-    stake_info.amount = amount;
+    let vault_amount_before = ctx.accounts.vault_token_account.amount;
 
     // transfer token to vault
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.staker_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.vault_token_account.to_account_info(),
                 authority: ctx.accounts.staker.to_account_info(),
             },
         ),
         amount,
+        ctx.accounts.mint.decimals,
     )?;
 
+    // mints with a transfer fee deliver less than requested, so credit what the vault received
+    ctx.accounts.vault_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_amount_before)
+        .unwrap();
+
+    if received == 0 {
+        return Err(AppError::NoToken.into());
+    }
+
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    stake_info.staker = ctx.accounts.staker.key();
+    stake_info.mint = ctx.accounts.mint.key();
+    stake_info.stake_at = clock.slot;
+    stake_info.amount = received;
+
     Ok(())
 }
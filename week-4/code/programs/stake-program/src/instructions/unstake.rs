@@ -1,11 +1,21 @@
-use crate::contants::{REWARD_VAULT_SEED, STAKE_INFO_SEED};
+use crate::contants::{CONFIG_SEED, REWARD_VAULT_SEED, STAKE_INFO_SEED};
 use crate::errors::AppError;
-use crate::state::StakeInfo;
+use crate::state::{Config, StakeInfo};
 use anchor_lang::prelude::*;
-use anchor_spl::token::Transfer;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 
 #[derive(Accounts)]
@@ -13,8 +23,19 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub staker: Signer<'info>, // What happens if the staker provided is not the original creator of the stake information?
 
-    pub mint: Account<'info, Mint>, // What happens if the provided mint address does not match the mint address in the stake information?
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>, // What happens if the provided mint address does not match the mint address in the stake information?
 
+    // a missing position fails here with AccountNotInitialized
     #[account(
         mut,
         seeds = [STAKE_INFO_SEED, staker.key().as_ref()],
@@ -24,10 +45,11 @@ pub struct Unstake<'info> {
 
     #[account(
         mut,
-        token::mint = mint,
-        token::authority = stake_info,
+        associated_token::mint = mint,
+        associated_token::authority = stake_info,
+        associated_token::token_program = token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -35,26 +57,32 @@ pub struct Unstake<'info> {
         bump,
         token::mint = mint,
         token::authority = reward_vault,
+        token::token_program = token_program,
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = staker,
+        associated_token::token_program = token_program,
     )]
-    pub staker_token_account: Account<'info, TokenAccount>,
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 
 pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+    if ctx.accounts.config.paused {
+        return Err(AppError::Paused.into());
+    }
+
     let stake_info = &ctx.accounts.stake_info;
 
-    if !stake_info.is_staked {
+    if stake_info.amount == 0 {
         return Err(AppError::NotStaked.into());
     }
 
@@ -73,17 +101,19 @@ pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
     // transfer reward to staker
     let reward_vault_bump = ctx.bumps.reward_vault;
     let reward_vault_signer_seeds: &[&[&[u8]]] = &[&[REWARD_VAULT_SEED, &[reward_vault_bump]]];
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.staker_token_account.to_account_info(),
                 authority: ctx.accounts.reward_vault.to_account_info(),
             },
             reward_vault_signer_seeds,
         ),
         reward,
+        ctx.accounts.mint.decimals,
     )?;
 
     // transfer token to vault
@@ -92,25 +122,77 @@ pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
     let stake_info_signer_seeds: &[&[&[u8]]] =
         &[&[STAKE_INFO_SEED, staker_key.as_ref(), &[stake_info_bump]]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.staker_token_account.to_account_info(),
                 authority: ctx.accounts.stake_info.to_account_info(),
             },
             stake_info_signer_seeds,
         ),
         stake_amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    // update stake_info
-    let stake_info = &mut ctx.accounts.stake_info;
+    // a vault holding withheld transfer fees cannot be closed, move them to the mint first
+    harvest_withheld_fees(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.vault_token_account.to_account_info(),
+    )?;
 
-    stake_info.stake_at = clock.slot;
-    stake_info.is_staked = false;
-    stake_info.amount = 0;
+    // close vault token account and return rent to staker
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.staker.to_account_info(),
+            authority: ctx.accounts.stake_info.to_account_info(),
+        },
+        stake_info_signer_seeds,
+    ))?;
+
+    // close stake_info and return rent to staker
+    ctx.accounts
+        .stake_info
+        .close(ctx.accounts.staker.to_account_info())?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn harvest_withheld_fees<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault_token_account: AccountInfo<'info>,
+) -> Result<()> {
+    if *vault_token_account.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let withheld_amount = {
+        let vault_data = vault_token_account.try_borrow_data()?;
+        let vault = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&vault_data)?;
+        vault
+            .get_extension::<TransferFeeAmount>()
+            .map(|transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount))
+            .unwrap_or(0)
+    };
+
+    if withheld_amount == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &harvest_withheld_tokens_to_mint(
+            &spl_token_2022::ID,
+            mint.key,
+            &[vault_token_account.key],
+        )?,
+        &[mint, vault_token_account, token_program],
+    )?;
+
+    Ok(())
+}
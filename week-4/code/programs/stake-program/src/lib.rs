@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 use instructions::*;
 
-mod contants;
-mod errors;
-mod instructions;
-mod state;
+pub mod contants;
+pub mod errors;
+pub mod instructions;
+pub mod state;
 
 declare_id!("5ZH5NAc5AeWpYW5MgxDgsHSPjzBYmN6qbn1dSwTYBj6X");
 
@@ -23,4 +23,24 @@ pub mod stake_program {
     pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
         instructions::unstake(ctx)
     }
+
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+        instructions::emergency_unstake(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn admin_withdraw(ctx: Context<AdminWithdraw>, amount: u64) -> Result<()> {
+        instructions::admin_withdraw(ctx, amount)
+    }
 }
@@ -9,7 +9,16 @@ pub struct StakeInfo {
 
     pub stake_at: u64,
 
-    pub is_staked: bool,
-
     pub amount: u64,
 }
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+
+    // proposed by the admin, becomes admin once it accepts
+    pub pending_admin: Pubkey,
+
+    pub paused: bool,
+}
@@ -0,0 +1,780 @@
+use anchor_lang::{
+    error::ErrorCode, solana_program::instruction::Instruction, AccountDeserialize,
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee, ExtensionType},
+    },
+    token_interface::spl_token_2022::state::Account as TokenAccountState,
+};
+use litesvm::LiteSVM;
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::InstructionError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+use std::path::Path;
+use stake_program::{
+    contants::{CONFIG_SEED, REWARD_VAULT_SEED, STAKE_INFO_SEED},
+    errors::AppError,
+    state::{Config, StakeInfo},
+};
+
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+const STAKER_BALANCE: u64 = 1_000 * ONE_TOKEN;
+const REWARD_VAULT_BALANCE: u64 = 1_000 * ONE_TOKEN;
+const STAKE_AMOUNT: u64 = 100 * ONE_TOKEN;
+
+// built by `anchor build`, litesvm runs the deployed SBF binary
+// run `anchor build && cargo test`, every test is skipped while it is missing
+const PROGRAM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/deploy/stake_program.so"
+);
+
+type TransactionResult = Result<(), TransactionError>;
+
+struct TestEnv {
+    svm: LiteSVM,
+    payer: Keypair,
+    token_program: Pubkey,
+    mint: Pubkey,
+    admin: Keypair,
+    staker: Keypair,
+}
+
+impl TestEnv {
+    fn new() -> Option<Self> {
+        Self::with_mint(spl_token::ID, None)
+    }
+
+    // transfer_fee_basis_points creates a Token-2022 mint carrying a TransferFeeConfig
+    fn with_mint(token_program: Pubkey, transfer_fee_basis_points: Option<u16>) -> Option<Self> {
+        let mut env = Self::start(token_program)?;
+
+        env.mint = env.create_mint(transfer_fee_basis_points);
+        let admin = env.admin.insecure_clone();
+        env.initialize(&admin).unwrap();
+
+        let staker = env.staker.insecure_clone();
+        env.fund_wallet(&staker, STAKER_BALANCE);
+        env.mint_to(env.reward_vault(), REWARD_VAULT_BALANCE);
+
+        Some(env)
+    }
+
+    // starts without a mint or config, admin is the upgrade authority of the program
+    // None when the program isn't built, the test is then skipped
+    fn start(token_program: Pubkey) -> Option<Self> {
+        if !Path::new(PROGRAM_PATH).exists() {
+            eprintln!("skipped: {PROGRAM_PATH} is missing, run `anchor build` before `cargo test`");
+            return None;
+        }
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(stake_program::ID, PROGRAM_PATH)
+            .unwrap();
+
+        // litesvm deploys with the non-upgradeable loader, so the program data is added by hand
+        let admin = Keypair::new();
+        let program_data = Account {
+            lamports: 1_000_000_000,
+            data: bincode::serialize(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(admin.pubkey()),
+            })
+            .unwrap(),
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(program_data_address(), program_data)
+            .unwrap();
+
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), 100_000_000_000).unwrap();
+        svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+
+        Some(Self {
+            svm,
+            payer,
+            token_program,
+            mint: Pubkey::default(),
+            admin,
+            staker: Keypair::new(),
+        })
+    }
+
+    fn payer(&self) -> Keypair {
+        self.payer.insecure_clone()
+    }
+
+    fn config(&self) -> Pubkey {
+        Pubkey::find_program_address(&[CONFIG_SEED], &stake_program::ID).0
+    }
+
+    fn reward_vault(&self) -> Pubkey {
+        Pubkey::find_program_address(&[REWARD_VAULT_SEED], &stake_program::ID).0
+    }
+
+    fn stake_info(&self, staker: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[STAKE_INFO_SEED, staker.as_ref()], &stake_program::ID).0
+    }
+
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program)
+    }
+
+    fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+
+        // a fresh blockhash keeps repeated identical transactions apart
+        self.svm.expire_blockhash();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+
+        self.svm
+            .send_transaction(transaction)
+            .map(|_| ())
+            .map_err(|failed| failed.err)
+    }
+
+    fn create_mint(&mut self, transfer_fee_basis_points: Option<u16>) -> Pubkey {
+        let payer = self.payer();
+        let mint = Keypair::new();
+
+        let extensions = match transfer_fee_basis_points {
+            Some(_) => vec![ExtensionType::TransferFeeConfig],
+            None => vec![],
+        };
+        let space = if self.token_program == spl_token_2022::ID {
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)
+                .unwrap()
+        } else {
+            spl_token::state::Mint::LEN
+        };
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            self.svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &self.token_program,
+        )];
+        if let Some(basis_points) = transfer_fee_basis_points {
+            instructions.push(
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &self.token_program,
+                    &mint.pubkey(),
+                    Some(&payer.pubkey()),
+                    Some(&payer.pubkey()),
+                    basis_points,
+                    u64::MAX,
+                )
+                .unwrap(),
+            );
+        }
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &self.token_program,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        );
+
+        self.process(&instructions, &[&mint]).unwrap();
+
+        mint.pubkey()
+    }
+
+    fn mint_to(&mut self, token_account: Pubkey, amount: u64) {
+        let payer = self.payer();
+        let instruction = spl_token_2022::instruction::mint_to(
+            &self.token_program,
+            &self.mint,
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+
+        self.process(&[instruction], &[]).unwrap();
+    }
+
+    fn fund_wallet(&mut self, wallet: &Keypair, amount: u64) {
+        let payer = self.payer();
+        let instructions = [
+            system_instruction::transfer(&payer.pubkey(), &wallet.pubkey(), 1_000_000_000),
+            create_associated_token_account(
+                &payer.pubkey(),
+                &wallet.pubkey(),
+                &self.mint,
+                &self.token_program,
+            ),
+        ];
+
+        self.process(&instructions, &[]).unwrap();
+        self.mint_to(self.ata(&wallet.pubkey(), &self.mint), amount);
+    }
+
+    fn initialize(&mut self, admin: &Keypair) -> TransactionResult {
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::Initialize {
+                admin: admin.pubkey(),
+                program_data: program_data_address(),
+                config: self.config(),
+                mint: self.mint,
+                reward_vault: self.reward_vault(),
+                system_program: system_program::ID,
+                token_program: self.token_program,
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::Initialize {}.data(),
+        };
+
+        self.process(&[instruction], &[admin])
+    }
+
+    fn stake(&mut self, staker: &Keypair, amount: u64) -> TransactionResult {
+        let stake_info = self.stake_info(&staker.pubkey());
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::Stake {
+                staker: staker.pubkey(),
+                config: self.config(),
+                mint: self.mint,
+                stake_info,
+                vault_token_account: self.ata(&stake_info, &self.mint),
+                staker_token_account: self.ata(&staker.pubkey(), &self.mint),
+                system_program: system_program::ID,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::Stake { amount }.data(),
+        };
+
+        self.process(&[instruction], &[staker])
+    }
+
+    fn unstake(
+        &mut self,
+        staker: &Keypair,
+        stake_info_owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> TransactionResult {
+        let stake_info = self.stake_info(stake_info_owner);
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::Unstake {
+                staker: staker.pubkey(),
+                config: self.config(),
+                mint: *mint,
+                stake_info,
+                vault_token_account: self.ata(&stake_info, &self.mint),
+                reward_vault: self.reward_vault(),
+                staker_token_account: self.ata(&staker.pubkey(), mint),
+                system_program: system_program::ID,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::Unstake {}.data(),
+        };
+
+        self.process(&[instruction], &[staker])
+    }
+
+    fn emergency_unstake(&mut self, staker: &Keypair) -> TransactionResult {
+        let stake_info = self.stake_info(&staker.pubkey());
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::EmergencyUnstake {
+                staker: staker.pubkey(),
+                config: self.config(),
+                mint: self.mint,
+                stake_info,
+                vault_token_account: self.ata(&stake_info, &self.mint),
+                staker_token_account: self.ata(&staker.pubkey(), &self.mint),
+                system_program: system_program::ID,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::EmergencyUnstake {}.data(),
+        };
+
+        self.process(&[instruction], &[staker])
+    }
+
+    fn set_paused(&mut self, admin: &Keypair, paused: bool) -> TransactionResult {
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::SetPaused {
+                admin: admin.pubkey(),
+                config: self.config(),
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::SetPaused { paused }.data(),
+        };
+
+        self.process(&[instruction], &[admin])
+    }
+
+    fn propose_admin(&mut self, admin: &Keypair, new_admin: &Pubkey) -> TransactionResult {
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::ProposeAdmin {
+                admin: admin.pubkey(),
+                config: self.config(),
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::ProposeAdmin {
+                new_admin: *new_admin,
+            }
+            .data(),
+        };
+
+        self.process(&[instruction], &[admin])
+    }
+
+    fn accept_admin(&mut self, pending_admin: &Keypair) -> TransactionResult {
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::AcceptAdmin {
+                pending_admin: pending_admin.pubkey(),
+                config: self.config(),
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::AcceptAdmin {}.data(),
+        };
+
+        self.process(&[instruction], &[pending_admin])
+    }
+
+    fn admin_withdraw(&mut self, admin: &Keypair, amount: u64) -> TransactionResult {
+        let instruction = Instruction {
+            program_id: stake_program::ID,
+            accounts: stake_program::accounts::AdminWithdraw {
+                admin: admin.pubkey(),
+                config: self.config(),
+                mint: self.mint,
+                reward_vault: self.reward_vault(),
+                admin_token_account: self.ata(&admin.pubkey(), &self.mint),
+                system_program: system_program::ID,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: stake_program::instruction::AdminWithdraw { amount }.data(),
+        };
+
+        self.process(&[instruction], &[admin])
+    }
+
+    fn config_account(&mut self) -> Config {
+        let account = self.svm.get_account(&self.config()).unwrap();
+
+        Config::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn token_balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self.svm.get_account(&token_account).unwrap();
+
+        // the base account layout is shared by spl-token and Token-2022
+        TokenAccountState::unpack_from_slice(&account.data[..TokenAccountState::LEN])
+            .unwrap()
+            .amount
+    }
+
+    fn account_exists(&mut self, address: Pubkey) -> bool {
+        self.svm.get_account(&address).is_some()
+    }
+
+    fn stake_info_account(&mut self, staker: &Pubkey) -> StakeInfo {
+        let account = self.svm.get_account(&self.stake_info(staker)).unwrap();
+
+        StakeInfo::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn current_slot(&mut self) -> u64 {
+        self.svm.get_sysvar::<Clock>().slot
+    }
+}
+
+fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[stake_program::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+fn assert_error(result: TransactionResult, code: u32) {
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code))
+    );
+}
+
+#[test]
+fn stake_transfers_tokens_to_vault() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+
+    let stake_info = env.stake_info_account(&staker.pubkey());
+    assert_eq!(stake_info.staker, staker.pubkey());
+    assert_eq!(stake_info.mint, env.mint);
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+    assert_eq!(stake_info.stake_at, env.current_slot());
+
+    let vault = env.ata(&env.stake_info(&staker.pubkey()), &env.mint);
+    let staker_ata = env.ata(&staker.pubkey(), &env.mint);
+    assert_eq!(env.token_balance(vault), STAKE_AMOUNT);
+    assert_eq!(env.token_balance(staker_ata), STAKER_BALANCE - STAKE_AMOUNT);
+}
+
+#[test]
+fn unstake_pays_exact_reward_after_warp() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    let stake_at = env.stake_info_account(&staker.pubkey()).stake_at;
+
+    let slots_passed = 250;
+    env.svm.warp_to_slot(stake_at + slots_passed);
+
+    let mint = env.mint;
+    env.unstake(&staker, &staker.pubkey(), &mint).unwrap();
+
+    // 1 token per slot
+    let reward = slots_passed * ONE_TOKEN;
+    let staker_ata = env.ata(&staker.pubkey(), &env.mint);
+    assert_eq!(env.token_balance(staker_ata), STAKER_BALANCE + reward);
+    assert_eq!(
+        env.token_balance(env.reward_vault()),
+        REWARD_VAULT_BALANCE - reward
+    );
+}
+
+#[test]
+fn unstake_closes_stake_info_and_vault() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    let stake_at = env.stake_info_account(&staker.pubkey()).stake_at;
+    env.svm.warp_to_slot(stake_at + 10);
+
+    let mint = env.mint;
+    env.unstake(&staker, &staker.pubkey(), &mint).unwrap();
+
+    let stake_info = env.stake_info(&staker.pubkey());
+    let vault = env.ata(&stake_info, &env.mint);
+    assert!(!env.account_exists(stake_info));
+    assert!(!env.account_exists(vault));
+
+    // the position can be opened again after closing
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    assert_eq!(
+        env.stake_info_account(&staker.pubkey()).amount,
+        STAKE_AMOUNT
+    );
+}
+
+#[test]
+fn stake_twice_fails_with_is_staked() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+
+    let result = env.stake(&staker, STAKE_AMOUNT);
+    assert_error(result, AppError::IsStaked.into());
+}
+
+#[test]
+fn stake_zero_fails_with_no_token() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    let result = env.stake(&staker, 0);
+    assert_error(result, AppError::NoToken.into());
+}
+
+#[test]
+fn unstake_without_position_fails() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    let mint = env.mint;
+    let result = env.unstake(&staker, &staker.pubkey(), &mint);
+    assert_error(result, ErrorCode::AccountNotInitialized.into());
+
+    // unstaking creates no position account
+    let stake_info = env.stake_info(&staker.pubkey());
+    assert!(!env.account_exists(stake_info));
+    assert!(!env.account_exists(env.ata(&stake_info, &mint)));
+
+    // a closed position cannot be unstaked twice
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    env.unstake(&staker, &staker.pubkey(), &mint).unwrap();
+    let result = env.unstake(&staker, &staker.pubkey(), &mint);
+    assert_error(result, ErrorCode::AccountNotInitialized.into());
+}
+
+#[test]
+fn unstake_with_wrong_mint_fails() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+
+    let mint = env.mint;
+    let wrong_mint = env.create_mint(None);
+    env.mint = wrong_mint;
+    env.fund_wallet(&staker, 0);
+    env.mint = mint;
+
+    let result = env.unstake(&staker, &staker.pubkey(), &wrong_mint);
+    assert_error(result, ErrorCode::ConstraintTokenMint.into());
+}
+
+#[test]
+fn unstake_with_wrong_staker_fails() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let attacker = Keypair::new();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    env.fund_wallet(&attacker, 0);
+
+    let mint = env.mint;
+    let result = env.unstake(&attacker, &staker.pubkey(), &mint);
+    assert_error(result, ErrorCode::ConstraintSeeds.into());
+
+    assert_eq!(
+        env.stake_info_account(&staker.pubkey()).amount,
+        STAKE_AMOUNT
+    );
+}
+
+#[test]
+fn stake_credits_amount_received_after_transfer_fee() {
+    // 1% transfer fee
+    let Some(mut env) = TestEnv::with_mint(spl_token_2022::ID, Some(100)) else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+
+    let fee = STAKE_AMOUNT / 100;
+    let stake_info = env.stake_info(&staker.pubkey());
+    let vault = env.ata(&stake_info, &env.mint);
+    assert_eq!(
+        env.stake_info_account(&staker.pubkey()).amount,
+        STAKE_AMOUNT - fee
+    );
+    assert_eq!(env.token_balance(vault), STAKE_AMOUNT - fee);
+
+    // withheld fees are harvested so the vault can be closed
+    let mint = env.mint;
+    env.unstake(&staker, &staker.pubkey(), &mint).unwrap();
+    assert!(!env.account_exists(stake_info));
+    assert!(!env.account_exists(vault));
+}
+
+#[test]
+fn initialize_is_restricted_to_the_upgrade_authority() {
+    let Some(mut env) = TestEnv::start(spl_token::ID) else {
+        return;
+    };
+    let admin = env.admin.insecure_clone();
+    let squatter = Keypair::new();
+    env.mint = env.create_mint(None);
+    env.fund_wallet(&squatter, 0);
+
+    // the config can no longer be claimed by whoever calls initialize first
+    let result = env.initialize(&squatter);
+    assert_error(result, AppError::Unauthorized.into());
+    assert!(!env.account_exists(env.config()));
+
+    env.initialize(&admin).unwrap();
+    let config = env.config_account();
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.pending_admin, Pubkey::default());
+    assert!(!config.paused);
+}
+
+#[test]
+fn pause_blocks_staking_until_resumed() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let admin = env.admin.insecure_clone();
+    let staker = env.staker.insecure_clone();
+    let mint = env.mint;
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+
+    // only the admin can pause
+    let result = env.set_paused(&staker, true);
+    assert_error(result, AppError::Unauthorized.into());
+
+    env.set_paused(&admin, true).unwrap();
+    assert!(env.config_account().paused);
+
+    let result = env.unstake(&staker, &staker.pubkey(), &mint);
+    assert_error(result, AppError::Paused.into());
+    let other = Keypair::new();
+    env.fund_wallet(&other, STAKE_AMOUNT);
+    let result = env.stake(&other, STAKE_AMOUNT);
+    assert_error(result, AppError::Paused.into());
+
+    env.set_paused(&admin, false).unwrap();
+    env.unstake(&staker, &staker.pubkey(), &mint).unwrap();
+    env.stake(&other, STAKE_AMOUNT).unwrap();
+}
+
+#[test]
+fn emergency_unstake_returns_principal_only_while_paused() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let admin = env.admin.insecure_clone();
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).unwrap();
+    let stake_at = env.stake_info_account(&staker.pubkey()).stake_at;
+    env.svm.warp_to_slot(stake_at + 100);
+
+    let result = env.emergency_unstake(&staker);
+    assert_error(result, AppError::NotPaused.into());
+
+    env.set_paused(&admin, true).unwrap();
+    env.emergency_unstake(&staker).unwrap();
+
+    // principal back, no reward paid out of the vault
+    let staker_ata = env.ata(&staker.pubkey(), &env.mint);
+    assert_eq!(env.token_balance(staker_ata), STAKER_BALANCE);
+    assert_eq!(env.token_balance(env.reward_vault()), REWARD_VAULT_BALANCE);
+    let stake_info = env.stake_info(&staker.pubkey());
+    assert!(!env.account_exists(stake_info));
+    assert!(!env.account_exists(env.ata(&stake_info, &env.mint)));
+
+    let result = env.emergency_unstake(&staker);
+    assert_error(result, ErrorCode::AccountNotInitialized.into());
+}
+
+#[test]
+fn admin_rotation_takes_effect_once_accepted() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let admin = env.admin.insecure_clone();
+    let new_admin = Keypair::new();
+    let staker = env.staker.insecure_clone();
+    env.fund_wallet(&new_admin, 0);
+
+    let result = env.propose_admin(&staker, &new_admin.pubkey());
+    assert_error(result, AppError::Unauthorized.into());
+
+    env.propose_admin(&admin, &new_admin.pubkey()).unwrap();
+    let config = env.config_account();
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.pending_admin, new_admin.pubkey());
+
+    // the current admin keeps its rights until the proposal is accepted
+    env.set_paused(&admin, true).unwrap();
+    let result = env.set_paused(&new_admin, false);
+    assert_error(result, AppError::Unauthorized.into());
+
+    let result = env.accept_admin(&staker);
+    assert_error(result, AppError::Unauthorized.into());
+
+    env.accept_admin(&new_admin).unwrap();
+    let config = env.config_account();
+    assert_eq!(config.admin, new_admin.pubkey());
+    assert_eq!(config.pending_admin, Pubkey::default());
+
+    let result = env.set_paused(&admin, false);
+    assert_error(result, AppError::Unauthorized.into());
+    env.set_paused(&new_admin, false).unwrap();
+    assert!(!env.config_account().paused);
+
+    // the cleared proposal cannot be accepted again
+    let result = env.accept_admin(&new_admin);
+    assert_error(result, AppError::Unauthorized.into());
+}
+
+#[test]
+fn admin_withdraw_moves_tokens_out_of_the_reward_vault() {
+    let Some(mut env) = TestEnv::new() else {
+        return;
+    };
+    let admin = env.admin.insecure_clone();
+    let staker = env.staker.insecure_clone();
+
+    let result = env.admin_withdraw(&staker, ONE_TOKEN);
+    assert_error(result, AppError::Unauthorized.into());
+
+    let result = env.admin_withdraw(&admin, 0);
+    assert_error(result, AppError::NoToken.into());
+
+    let result = env.admin_withdraw(&admin, REWARD_VAULT_BALANCE + 1);
+    assert_error(result, AppError::NoToken.into());
+
+    let amount = 400 * ONE_TOKEN;
+    env.admin_withdraw(&admin, amount).unwrap();
+    assert_eq!(
+        env.token_balance(env.ata(&admin.pubkey(), &env.mint)),
+        amount
+    );
+    assert_eq!(
+        env.token_balance(env.reward_vault()),
+        REWARD_VAULT_BALANCE - amount
+    );
+
+    // the vault can be drained completely
+    env.admin_withdraw(&admin, REWARD_VAULT_BALANCE - amount)
+        .unwrap();
+    assert_eq!(env.token_balance(env.reward_vault()), 0);
+}
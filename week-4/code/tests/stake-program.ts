@@ -28,6 +28,8 @@ describe("stake-program", () => {
   const usdcMintKp = anchor.web3.Keypair.generate();
   let rewardVault: anchor.web3.PublicKey;
   let stakeInfo: anchor.web3.PublicKey;
+  let config: anchor.web3.PublicKey;
+  let programData: anchor.web3.PublicKey;
 
   before(async () => {
     // init staker
@@ -102,6 +104,17 @@ describe("stake-program", () => {
       [Buffer.from("reward")],
       program.programId
     )[0];
+
+    config = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    )[0];
+
+    // the provider wallet deploys the program, so it is the upgrade authority
+    programData = anchor.web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBytes()],
+      anchor.web3.BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    )[0];
   });
 
   it("Is initialized!", async () => {
@@ -109,6 +122,8 @@ describe("stake-program", () => {
       .initialize()
       .accounts({
         admin: provider.publicKey,
+        programData: programData,
+        config: config,
         rewardVault: rewardVault,
         mint: usdcMintKp.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
@@ -127,9 +142,13 @@ describe("stake-program", () => {
       rewardVault.toBase58()
     );
     expect(Number(rewardVaultAccount.amount)).to.equal(0);
-  });
 
-  it("Stake successfully", async () => {
+    const configAccount = await program.account.config.fetch(config);
+
+    expect(configAccount.admin.toBase58()).to.equal(
+      provider.publicKey.toBase58()
+    );
+x, async () => {
     stakeInfo = anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("stake_info"), staker.publicKey.toBytes()],
       program.programId
@@ -147,6 +166,7 @@ describe("stake-program", () => {
       .stake(stakeAmount)
       .accounts({
         staker: staker.publicKey,
+        config: config,
         mint: usdcMintKp.publicKey,
         stakeInfo: stakeInfo,
         vaultTokenAccount: vaultTokenAccount,
@@ -168,7 +188,6 @@ describe("stake-program", () => {
     expect(stakeInfoAccount.mint.toBase58()).to.equal(
       usdcMintKp.publicKey.toBase58()
     );
-    expect(stakeInfoAccount.isStaked).to.equal(true);
     expect(stakeInfoAccount.amount.toString()).to.equal(stakeAmount.toString());
 
     const stakerAccount = await getAccount(
@@ -211,6 +230,7 @@ describe("stake-program", () => {
       .unstake()
       .accounts({
         staker: staker.publicKey,
+        config: config,
         mint: usdcMintKp.publicKey,
         stakeInfo: stakeInfo,
         vaultTokenAccount: vaultTokenAccount,
@@ -225,10 +245,11 @@ describe("stake-program", () => {
 
     console.log("Your transaction signature", tx);
 
-    const stakeInfoAccount = await program.account.stakeInfo.fetch(stakeInfo);
+    const stakeInfoAccount = await program.account.stakeInfo.fetchNullable(
+      stakeInfo
+    );
 
-    expect(stakeInfoAccount.isStaked).to.equal(false);
-    expect(Number(stakeInfoAccount.amount)).to.equal(0);
+    expect(stakeInfoAccount).to.equal(null);
 
     const stakerAccount = await getAccount(
       provider.connection,
@@ -240,13 +261,167 @@ describe("stake-program", () => {
       rewardVault
     );
 
-    const vaultAccount = await getAccount(
-      provider.connection,
+    const vaultAccountInfo = await provider.connection.getAccountInfo(
       vaultTokenAccount
     );
 
     expect(Number(stakerAccount.amount)).to.greaterThan(1000 * 10 ** 6);
-    expect(Number(vaultAccount.amount)).to.equal(0);
+    expect(vaultAccountInfo).to.equal(null);
     expect(Number(rewardVaultAccount.amount)).to.lessThan(1000 * 10 ** 6);
   });
+
+  it("Emergency unstake while paused", async () => {
+    const vaultTokenAccount = getAssociatedTokenAddressSync(
+      usdcMintKp.publicKey,
+      stakeInfo,
+      true
+    );
+
+    const stakeAmount = new BN(100 * 10 ** 6);
+
+    await program.methods
+      .stake(stakeAmount)
+      .accounts({
+        staker: staker.publicKey,
+        config: config,
+        mint: usdcMintKp.publicKey,
+        stakeInfo: stakeInfo,
+        vaultTokenAccount: vaultTokenAccount,
+        stakerTokenAccount: stakerTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    await program.methods
+      .setPaused(true)
+      .accounts({
+        admin: provider.publicKey,
+        config: config,
+      })
+      .rpc();
+
+    const stakerAccountBefore = await getAccount(
+      provider.connection,
+      stakerTokenAccount
+    );
+
+    try {
+      await program.methods
+        .unstake()
+        .accounts({
+          staker: staker.publicKey,
+          config: config,
+          mint: usdcMintKp.publicKey,
+          stakeInfo: stakeInfo,
+          vaultTokenAccount: vaultTokenAccount,
+          rewardVault: rewardVault,
+          stakerTokenAccount: stakerTokenAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+      expect.fail("unstake should fail while paused");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("Paused");
+    }
+
+    const tx = await program.methods
+      .emergencyUnstake()
+      .accounts({
+        staker: staker.publicKey,
+        config: config,
+        mint: usdcMintKp.publicKey,
+        stakeInfo: stakeInfo,
+        vaultTokenAccount: vaultTokenAccount,
+        stakerTokenAccount: stakerTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    const stakerAccountAfter = await getAccount(
+      provider.connection,
+      stakerTokenAccount
+    );
+
+    // principal only, no reward
+    expect(
+      (stakerAccountAfter.amount - stakerAccountBefore.amount).toString()
+    ).to.equal(stakeAmount.toString());
+    expect(await program.account.stakeInfo.fetchNullable(stakeInfo)).to.equal(
+      null
+    );
+  });
+
+  it("Rotate admin", async () => {
+    const newAdmin = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({
+        admin: provider.publicKey,
+        config: config,
+      })
+      .rpc();
+
+    // the proposal does not change the admin until it is accepted
+    let configAccount = await program.account.config.fetch(config);
+
+    expect(configAccount.admin.toBase58()).to.equal(
+      provider.publicKey.toBase58()
+    );
+    expect(configAccount.pendingAdmin.toBase58()).to.equal(
+      newAdmin.publicKey.toBase58()
+    );
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        pendingAdmin: newAdmin.publicKey,
+        config: config,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    try {
+      await program.methods
+        .setPaused(false)
+        .accounts({
+          admin: provider.publicKey,
+          config: config,
+        })
+        .rpc();
+      expect.fail("old admin should be rejected");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await program.methods
+      .setPaused(false)
+      .accounts({
+        admin: newAdmin.publicKey,
+        config: config,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    configAccount = await program.account.config.fetch(config);
+
+    expect(configAccount.admin.toBase58()).to.equal(
+      newAdmin.publicKey.toBase58()
+    );
+    expect(configAccount.pendingAdmin.toBase58()).to.equal(
+      anchor.web3.PublicKey.default.toBase58()
+    );
+    expect(configAccount.paused).to.equal(false);
+  });
 });
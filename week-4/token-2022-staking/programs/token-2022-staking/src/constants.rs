@@ -10,10 +10,33 @@ pub const POOL_SEED: &[u8] = b"pool";
 pub const STAKEINFO_SEED: &[u8] = b"stakeinfo";
 
 #[constant]
-pub const MINT_URI: &str = "https://raw.githubusercontent.com/HongThaiPham/solana-bootcamp-autumn-2024/main/week-4/token-2022-staking/app/assets/token-info.json";
+pub const RECEIPT_SEED: &[u8] = b"receipt";
 
 #[constant]
-pub const MINT_NAME: &str = "Solana Bootcamp Token";
+pub const SHARE_SEED: &[u8] = b"share";
 
 #[constant]
-pub const MINT_SYMBOL: &str = "SBT";
+pub const UNBONDING_SEED: &[u8] = b"unbonding";
+
+#[constant]
+pub const VESTING_SEED: &[u8] = b"vesting";
+
+#[constant]
+pub const VOTER_WEIGHT_SEED: &[u8] = b"voter-weight-record";
+
+// unbonding period giving the maximum voting bonus, a year in seconds
+// the voter weight goes from 1x the stake without unbonding period to 2x at this period
+#[constant]
+pub const MAX_LOCKUP_BONUS_PERIOD: u64 = 365 * 24 * 60 * 60;
+
+// reward tokens a pool can distribute on top of its reward_mint
+#[constant]
+pub const MAX_EXTRA_REWARDS: usize = 2;
+
+// vesting schedules a staker keeps at once, one per claim
+#[constant]
+pub const MAX_VESTING_TRANCHES: usize = 8;
+
+// scale of Pool.acc_reward_per_share to keep precision of the integer division
+#[constant]
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
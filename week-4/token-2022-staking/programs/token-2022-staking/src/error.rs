@@ -8,12 +8,70 @@ pub enum MyErrorCode {
     InvalidStakeMintAccount,
     #[msg("Allocation must be greater than zero")]
     AllocationMustBeGreaterThanZero,
-    #[msg("Reward per second must be greater than zero")]
-    RewardPerSecondMustBeGreaterThanZero,
+    #[msg("Reward rate must be greater than zero")]
+    RewardRateMustBeGreaterThanZero,
     #[msg("Invalid unstake amount")]
     InvalidUnstakeAmount,
     #[msg("Insufficient stake amount")]
     InsufficientStakeAmount,
     #[msg("Overflow")]
     Overflow,
+    #[msg("Emission end must be in the future")]
+    InvalidEmissionEnd,
+    #[msg("Insufficient reward balance in the pool")]
+    InsufficientRewardBalance,
+    #[msg("No reward to claim")]
+    NoRewardToClaim,
+    #[msg("Pool is paused")]
+    PoolPaused,
+    #[msg("Pool still has staked tokens")]
+    PoolNotEmpty,
+    #[msg("Reward funder account is required for a deposited reward mint")]
+    MissingRewardFunder,
+    #[msg("Pool already distributes the maximum number of extra rewards")]
+    TooManyExtraRewards,
+    #[msg("Reward mint is already distributed by the pool")]
+    DuplicateRewardMint,
+    #[msg("Invalid extra reward accounts")]
+    InvalidExtraRewardAccounts,
+    #[msg("Stake mint has an unsupported Token-2022 extension")]
+    UnsupportedStakeMintExtension,
+    #[msg("Invalid receipt mode")]
+    InvalidReceiptMode,
+    #[msg("Pool already has a receipt mint")]
+    ReceiptMintAlreadySet,
+    #[msg("Receipt accounts are required by the pool")]
+    MissingReceiptAccounts,
+    #[msg("Instruction is not available in the stake mode of the pool")]
+    InvalidStakeMode,
+    #[msg("Liquid staking requires the reward mint to be the stake mint")]
+    LiquidRewardMintMismatch,
+    #[msg("Amount is too small to mint or redeem a share")]
+    ZeroShares,
+    #[msg("Pool has an unbonding period, use request_unstake")]
+    UnbondingRequired,
+    #[msg("Unbonding period is not over yet")]
+    UnbondingNotFinished,
+    #[msg("Signer is neither the staker nor its claim delegate")]
+    InvalidClaimAuthority,
+    #[msg("Vesting cliff must not exceed its duration")]
+    InvalidVestingSchedule,
+    #[msg("Vesting accounts are required by the pool")]
+    MissingVestingAccounts,
+    #[msg("No vested reward to release")]
+    NothingToRelease,
+    #[msg("Invalid reward mint account")]
+    InvalidRewardMintAccount,
+    #[msg("Pool is not configured for governance")]
+    GovernanceNotConfigured,
+    #[msg("Stake info belongs to a closed pool at the same address")]
+    StaleStakeInfo,
+    #[msg("Pool still owes settled rewards to stakers")]
+    RewardsOwed,
+    #[msg("Reward mint has an unsupported Token-2022 extension")]
+    UnsupportedRewardMintExtension,
+    #[msg("Stake is locked by a vote")]
+    PositionLockedForVote,
+    #[msg("Vote lock period must not be zero")]
+    InvalidVoteLockPeriod,
 }
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::EmissionMode;
+
+// emitted with emit_cpi! so indexers can read them from the inner instructions even when logs are truncated
+
+#[event]
+pub struct ConfigInitialized {
+    pub config: Pubkey,
+    pub id: u64,
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PoolCreated {
+    pub config: Pubkey,
+    pub pool: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    // net of transfer fees for a deposited reward
+    pub allocation: u64,
+    pub reward_rate: u64,
+    pub emission_mode: EmissionMode,
+    pub emission_end: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    // owner of the position, the beneficiary of stake_for
+    pub staker: Pubkey,
+    // amount received by the pool, net of transfer fees
+    pub amount: u64,
+    pub total_staked: u64,
+    pub slot: u64,
+}
+
+// also emitted by request_unstake, the amount then waits in an unbonding ticket
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    // for request_unstake the amount held by the ticket, net of transfer fees
+    pub amount: u64,
+    pub total_staked: u64,
+    pub slot: u64,
+}
+
+// shares minted by liquid_stake, the amount is net of transfer fees
+#[event]
+pub struct LiquidStaked {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub total_staked: u64,
+    pub slot: u64,
+}
+
+// shares burned by liquid_unstake, the amount is the stake and compounded reward they were worth
+#[event]
+pub struct LiquidUnstaked {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub total_staked: u64,
+    pub slot: u64,
+}
+
+// reward of reward_mint paid by claim or unstake, extra rewards are not included
+#[event]
+pub struct RewardPaid {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    // sent to the vesting account of the staker
+    pub vested: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub slot: u64,
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    check_reward_mint_extensions, error::MyErrorCode, Config, ExtraReward, Pool, StakeMode,
+    CONFIG_SEED, MAX_EXTRA_REWARDS, POOL_SEED,
+};
+
+#[derive(Accounts)]
+pub struct AddExtraReward<'info> {
+    // only the authority of the config can change the pool, it deposits the extra reward
+    #[account(
+        mut,
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        // extra rewards are claimed through a StakeInfo
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    // the mint of the extra reward token
+    #[account(
+        mint::token_program = reward_token_program,
+        constraint = pool.reward_mint != reward_mint.key()
+            && pool.extra_rewards.iter().all(|extra_reward| extra_reward.mint != reward_mint.key())
+            @MyErrorCode::DuplicateRewardMint,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    // create a associated token account to keep the extra reward token for the pool
+    // init_if_needed so a known mint, whose reward_ata exists, is reported as DuplicateRewardMint
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // token account of the signer to deposit the extra reward from
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = signer,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddExtraReward<'info> {
+    pub fn handler(&mut self, allocation: u64, reward_rate: u64) -> Result<()> {
+        require_gt!(allocation, 0, MyErrorCode::AllocationMustBeGreaterThanZero);
+        require_gt!(reward_rate, 0, MyErrorCode::RewardRateMustBeGreaterThanZero);
+        require_gt!(
+            MAX_EXTRA_REWARDS,
+            self.pool.extra_rewards.len(),
+            MyErrorCode::TooManyExtraRewards
+        );
+        let reward_mint = self.reward_mint.key();
+        check_reward_mint_extensions(&self.reward_mint.to_account_info())?;
+
+        // accrue the existing rewards so the extra reward only starts emitting from now on
+        self.pool.update(&Clock::get()?)?;
+
+        // the allocation is what the pool received, net of transfer fees
+        let allocation = self.deposit_reward(allocation)?;
+        self.pool.extra_rewards.push(ExtraReward {
+            mint: reward_mint,
+            reward_ata: self.reward_ata.key(),
+            token_program: self.reward_token_program.key(),
+            allocation,
+            reward_rate,
+            distributed: 0,
+            acc_reward_per_share: 0,
+            owed: 0,
+        });
+        Ok(())
+    }
+
+    fn deposit_reward(&mut self, amount: u64) -> Result<u64> {
+        let balance_before = self.reward_ata.amount;
+
+        transfer_checked(
+            CpiContext::new(
+                self.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.funder_reward_ata.to_account_info(),
+                    to: self.reward_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                    mint: self.reward_mint.to_account_info(),
+                },
+            ),
+            amount,
+            self.reward_mint.decimals,
+        )?;
+
+        // the reward_ata may already hold tokens sent to the pool, only the deposit is allocated
+        self.reward_ata.reload()?;
+        Ok(self
+            .reward_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?)
+    }
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    close_position, error::MyErrorCode, pay_reward, transfer_extra_rewards, Pool, RewardAccounts,
+    StakeInfo, Vesting, POOL_SEED, STAKEINFO_SEED, VESTING_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    // signer is the staker or its claim delegate, it pays the staker_reward_ata if needed
+    #[account(
+      mut,
+      constraint = signer.key() == staker.key() || signer.key() == stake_info.claim_delegate @MyErrorCode::InvalidClaimAuthority,
+    )]
+    pub signer: Signer<'info>,
+    // owner of the stake_info, the reward always goes to it
+    // mut to receive the rent of the position once it is closed
+    #[account(mut)]
+    pub staker: SystemAccount<'info>,
+    #[account(
+      mut,
+      seeds = [POOL_SEED, pool.config.as_ref(), pool.stake_mint.as_ref()],
+      bump,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+      mut,
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), staker.key().as_ref()],
+      bump,
+      constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+    // closed with the stake_info once nothing is staked, unbonding nor owed anymore
+    #[account(
+      mut,
+      associated_token::mint = stake_mint,
+      associated_token::authority = stake_info,
+      associated_token::token_program = stake_token_program,
+    )]
+    pub stake_info_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // mut to harvest the transfer fees withheld in the stake_info_ata before closing it
+    #[account(
+      mut,
+      address = pool.stake_mint @MyErrorCode::InvalidStakeMintAccount,
+      mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+      address = pool.reward_mint,
+      mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+      mut,
+      associated_token::mint = reward_mint,
+      associated_token::authority = pool,
+      associated_token::token_program = reward_token_program,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // associated token account of the staker to receive reward token
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = reward_mint,
+      associated_token::authority = staker,
+      associated_token::token_program = reward_token_program,
+    )]
+    pub staker_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // vesting accounts, only required when the pool vests the reward
+    #[account(
+      init_if_needed,
+      payer = signer,
+      space = 8 + Vesting::INIT_SPACE,
+      seeds = [VESTING_SEED, pool.key().as_ref(), staker.key().as_ref()],
+      bump
+    )]
+    pub vesting: Option<Box<Account<'info, Vesting>>>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = reward_mint,
+      associated_token::authority = vesting,
+      associated_token::token_program = reward_token_program,
+    )]
+    pub vesting_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Claim<'info> {
+    // returns the reward of reward_mint paid
+    pub fn handler(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &ClaimBumps,
+    ) -> Result<u64> {
+        // settle the reward, the staked amount stays in the pool
+        let reward_amount = self.stake_info.claim(&mut self.pool, &Clock::get()?)?;
+        let extra_amounts = self.stake_info.take_extra_rewards(&mut self.pool)?;
+        require!(
+            reward_amount > 0 || extra_amounts.iter().any(|amount| *amount > 0),
+            MyErrorCode::NoRewardToClaim
+        );
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        // send the reward to the staker, or to its vesting account
+        pay_reward(
+            RewardAccounts {
+                pool: &self.pool,
+                staker: self.staker.key(),
+                reward_mint: &self.reward_mint,
+                reward_ata: &self.reward_ata,
+                staker_reward_ata: &self.staker_reward_ata,
+                vesting: self.vesting.as_deref_mut(),
+                vesting_ata: self.vesting_ata.as_deref_mut(),
+                reward_token_program: &self.reward_token_program,
+            },
+            reward_amount,
+            &[&seeds[..]],
+        )?;
+        // send the extra rewards to the staker, their accounts are passed in remaining_accounts
+        transfer_extra_rewards(
+            &self.pool,
+            remaining_accounts,
+            &extra_amounts,
+            &self.staker.key(),
+            &[&seeds[..]],
+        )?;
+        // the last reward of a position whose stake was all withdrawn through unbonding tickets
+        if self.stake_info.is_empty() {
+            let pool = self.pool.key();
+            let staker = self.staker.key();
+            let seeds = &[
+                STAKEINFO_SEED,
+                pool.as_ref(),
+                staker.as_ref(),
+                &[bumps.stake_info],
+            ];
+            close_position(
+                &mut self.stake_info,
+                &self.stake_info_ata,
+                &self.stake_mint,
+                &self.stake_token_program,
+                self.staker.to_account_info(),
+                &[&seeds[..]],
+            )?;
+        }
+        Ok(reward_amount)
+    }
+}
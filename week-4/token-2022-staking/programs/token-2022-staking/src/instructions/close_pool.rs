@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::{
+    close_extra_reward_atas, error::MyErrorCode, extra_reward_balances, transfer_extra_rewards,
+    Config, Pool, RewardSource, CONFIG_SEED, POOL_SEED,
+};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    // only the authority of the config can close the pool, rent goes back to it
+    #[account(
+        mut,
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        close = signer,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        address = pool.reward_mint,
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = pool.reward_ata,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // token account of the signer to return a deposited reward to
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = signer,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_reward_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClosePool<'info> {
+    pub fn handler(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &ClosePoolBumps,
+    ) -> Result<()> {
+        // every staker must have left
+        require_eq!(self.pool.total_staked, 0, MyErrorCode::PoolNotEmpty);
+        // the reward settled to stakers who left must be paid before the balances are returned or burned
+        require!(
+            self.pool.reward_owed == 0
+                && self
+                    .pool
+                    .extra_rewards
+                    .iter()
+                    .all(|extra_reward| extra_reward.owed == 0),
+            MyErrorCode::RewardsOwed
+        );
+
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // the reward_ata must be empty to be closed
+        // a deposited reward goes back to the signer, a minted reward is burned
+        if self.reward_ata.amount.gt(&0) && self.pool.reward_source == RewardSource::Deposited {
+            let funder_reward_ata = self
+                .funder_reward_ata
+                .as_ref()
+                .ok_or(MyErrorCode::MissingRewardFunder)?;
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.reward_token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.reward_ata.to_account_info(),
+                        to: funder_reward_ata.to_account_info(),
+                        authority: self.pool.to_account_info(),
+                        mint: self.reward_mint.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                self.reward_ata.amount,
+                self.reward_mint.decimals,
+            )?;
+        } else if self.reward_ata.amount.gt(&0) {
+            burn(
+                CpiContext::new_with_signer(
+                    self.reward_token_program.to_account_info(),
+                    Burn {
+                        mint: self.reward_mint.to_account_info(),
+                        from: self.reward_ata.to_account_info(),
+                        authority: self.pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                self.reward_ata.amount,
+            )?;
+        }
+
+        // extra rewards are always deposited, return them to the signer and close their reward_ata
+        let extra_balances = extra_reward_balances(&self.pool, remaining_accounts)?;
+        transfer_extra_rewards(
+            &self.pool,
+            remaining_accounts,
+            &extra_balances,
+            &self.signer.key(),
+            signer_seeds,
+        )?;
+        close_extra_reward_atas(
+            &self.pool,
+            remaining_accounts,
+            &self.signer.to_account_info(),
+            signer_seeds,
+        )?;
+
+        // close the reward_ata and return rent fee to the signer
+        close_account(CpiContext::new_with_signer(
+            self.reward_token_program.to_account_info(),
+            CloseAccount {
+                account: self.reward_ata.to_account_info(),
+                destination: self.signer.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        Ok(())
+    }
+}
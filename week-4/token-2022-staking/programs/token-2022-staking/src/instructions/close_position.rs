@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
+};
+
+use crate::{harvest_withheld_fees, StakeInfo};
+
+// close a position with nothing staked, unbonding nor owed anymore, its rent goes to `destination`
+// signer_seeds are the seeds of the stake_info, the authority of the stake_info_ata
+pub fn close_position<'info>(
+    stake_info: &mut Account<'info, StakeInfo>,
+    stake_info_ata: &InterfaceAccount<'info, TokenAccount>,
+    stake_mint: &InterfaceAccount<'info, Mint>,
+    stake_token_program: &Interface<'info, TokenInterface>,
+    destination: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // an account still holding withheld transfer fees can't be closed
+    harvest_withheld_fees(
+        &stake_token_program.to_account_info(),
+        &stake_mint.to_account_info(),
+        &stake_info_ata.to_account_info(),
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        stake_token_program.to_account_info(),
+        CloseAccount {
+            account: stake_info_ata.to_account_info(),
+            destination: destination.clone(),
+            authority: stake_info.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    stake_info.close(destination)
+}
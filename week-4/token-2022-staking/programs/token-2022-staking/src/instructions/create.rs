@@ -1,10 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, mint_to, MintTo}
+    associated_token::AssociatedToken,
+    token_interface::{
+        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
-use crate::{error::MyErrorCode, Config, Pool, CONFIG_SEED, POOL_SEED};
+use crate::{
+    check_mint_extensions, check_reward_mint_extensions, error::MyErrorCode, Config, EmissionMode,
+    Pool, ReceiptMode, RewardSource, StakeMode, CONFIG_SEED, POOL_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreatePool<'info> {
     // account that signs the transaction
@@ -15,7 +22,7 @@ pub struct CreatePool<'info> {
     pub signer: Signer<'info>,
     #[account(
         mut,
-        seeds = [CONFIG_SEED], // just one config account, so we use one string for the seed
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()], // a config account per id
         bump
     )]
     pub config: Account<'info, Config>,
@@ -29,25 +36,32 @@ pub struct CreatePool<'info> {
         init,
         payer = signer,
         space = 8 + Pool::INIT_SPACE,
-        seeds = [POOL_SEED, stake_mint.key().as_ref()], // because we will have multi pool for multi mint, so we use a string and the mint key as the seed
+        seeds = [POOL_SEED, config.key().as_ref(), stake_mint.key().as_ref()], // because we will have multi pool for multi mint in multi config, so we use a string, the config key and the mint key as the seed
         bump
     )]
     pub pool: Account<'info, Pool>,
     // the mint of the token to be rewarded
+    // the reward mint of the config is minted, any other mint is deposited by the signer
     #[account(
         mut,
-        address = config.reward_mint, // reward mint must be the same as the reward mint in the config account
         mint::token_program = reward_token_program, // check token program of the mint must be the same as the reward_token_program
-        mint::authority = config, // check authority of the mint must be config account
     )]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    // token account of the signer to deposit an external reward mint from
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = signer,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_reward_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     // create a associated token account to keep the reward token for the pool
     #[account(
         init,
         payer = signer,
         associated_token::mint = reward_mint,
-        associated_token::authority = pool, 
-        associated_token::token_program = reward_token_program,   
+        associated_token::authority = pool,
+        associated_token::token_program = reward_token_program,
     )]
     pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -57,22 +71,109 @@ pub struct CreatePool<'info> {
 }
 
 impl<'info> CreatePool<'info> {
-    pub fn handler(&mut self, allocation: u64,reward_per_second:u64, bumps: CreatePoolBumps) -> Result<()> {
+    pub fn handler(
+        &mut self,
+        allocation: u64,
+        reward_rate: u64,
+        emission_mode: EmissionMode,
+        emission_end: u64,
+        bumps: &CreatePoolBumps,
+    ) -> Result<()> {
         require_gt!(allocation, 0, MyErrorCode::AllocationMustBeGreaterThanZero);
-        require_gt!(reward_per_second, 0, MyErrorCode::RewardPerSecondMustBeGreaterThanZero);
-        self.pool.set_inner(Pool { 
-            authority: self.signer.to_account_info().key(), 
-            stake_mint: self.stake_mint.to_account_info().key(), 
-            reward_mint: self.reward_mint.to_account_info().key(), 
-            reward_ata: self.reward_ata.to_account_info().key() , 
-            allocation ,
-            reward_per_slot: reward_per_second
+        require_gt!(reward_rate, 0, MyErrorCode::RewardRateMustBeGreaterThanZero);
+        // a stake mint with a TransferFeeConfig is credited with the amount received after the fee
+        // the accounts of its TransferHook are passed in remaining_accounts of stake and unstake
+        check_mint_extensions(
+            &self.stake_mint.to_account_info(),
+            MyErrorCode::UnsupportedStakeMintExtension,
+        )?;
+        check_reward_mint_extensions(&self.reward_mint.to_account_info())?;
+        let reward_source = if self.reward_mint.key() == self.config.reward_mint {
+            RewardSource::Minted
+        } else {
+            RewardSource::Deposited
+        };
+        self.pool.set_inner(Pool {
+            config: self.config.key(),
+            nonce: self.config.pool_count,
+            authority: self.signer.to_account_info().key(),
+            stake_mint: self.stake_mint.to_account_info().key(),
+            reward_mint: self.reward_mint.to_account_info().key(),
+            reward_ata: self.reward_ata.to_account_info().key(),
+            allocation,
+            reward_rate,
+            total_staked: 0,
+            staker_count: 0,
+            acc_reward_per_share: 0,
+            last_update: 0,
+            distributed: 0,
+            reward_owed: 0,
+            emission_end,
+            paused: false,
+            emission_mode,
+            reward_source,
+            extra_rewards: vec![],
+            receipt_mode: ReceiptMode::None,
+            receipt_mint: Pubkey::default(),
+            stake_mode: StakeMode::Position,
+            share_mint: Pubkey::default(),
+            unbonding_period: 0,
+            vesting_cliff: 0,
+            vesting_duration: 0,
+            governance_realm: Pubkey::default(),
+            governing_token_mint: Pubkey::default(),
+            vote_lock_period: 0,
         });
-        self.mint_reward(allocation, bumps)?;
+        // a pool re-created at the same address gets a new nonce, stake infos of the old one are stale
+        self.config.pool_count = self
+            .config
+            .pool_count
+            .checked_add(1)
+            .ok_or(MyErrorCode::Overflow)?;
+        // emission starts now, in the unit chosen for the pool
+        let now = self.pool.now(&Clock::get()?);
+        require_gt!(emission_end, now, MyErrorCode::InvalidEmissionEnd);
+        self.pool.last_update = now;
+        match reward_source {
+            RewardSource::Minted => self.mint_reward(allocation, bumps)?,
+            RewardSource::Deposited => {
+                // the allocation is what the pool received, net of transfer fees
+                self.pool.allocation = self.deposit_reward(allocation)?;
+            }
+        }
         Ok(())
     }
 
-    fn mint_reward(&mut self, amount: u64, bumps: CreatePoolBumps) -> Result<()> {
+    fn deposit_reward(&mut self, amount: u64) -> Result<u64> {
+        let funder_reward_ata = self
+            .funder_reward_ata
+            .as_ref()
+            .ok_or(MyErrorCode::MissingRewardFunder)?;
+        let balance_before = self.reward_ata.amount;
+
+        transfer_checked(
+            CpiContext::new(
+                self.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: funder_reward_ata.to_account_info(),
+                    to: self.reward_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                    mint: self.reward_mint.to_account_info(),
+                },
+            ),
+            amount,
+            self.reward_mint.decimals,
+        )?;
+
+        self.reward_ata.reload()?;
+        Ok(self
+            .reward_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?)
+    }
+
+    fn mint_reward(&mut self, amount: u64, bumps: &CreatePoolBumps) -> Result<()> {
         msg!("Minting reward tokens");
 
         let cpi_accounts = MintTo {
@@ -80,12 +181,20 @@ impl<'info> CreatePool<'info> {
             to: self.reward_ata.to_account_info(),
             authority: self.config.to_account_info(),
         };
-      
-        let seeds = &[CONFIG_SEED, &[bumps.config]];
+
+        let id = self.config.id.to_le_bytes();
+        let seeds = &[CONFIG_SEED, id.as_ref(), &[bumps.config]];
         let signer_seeds = &[&seeds[..]];
 
-        mint_to(CpiContext::new_with_signer(self.reward_token_program.to_account_info(), cpi_accounts, signer_seeds), amount)?;
-      
+        mint_to(
+            CpiContext::new_with_signer(
+                self.reward_token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
         Ok(())
     }
 }
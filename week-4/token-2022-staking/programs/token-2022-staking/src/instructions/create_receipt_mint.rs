@@ -0,0 +1,164 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    token_2022::{
+        initialize_mint2,
+        spl_token_2022::{
+            self,
+            extension::{
+                non_transferable::NonTransferable, BaseStateWithExtensions, ExtensionType,
+                StateWithExtensions,
+            },
+            state::Mint,
+        },
+        InitializeMint2, Token2022,
+    },
+    token_2022_extensions::{non_transferable_mint_initialize, NonTransferableMintInitialize},
+    token_interface,
+};
+
+use crate::{
+    error::MyErrorCode, Config, Pool, ReceiptMode, StakeMode, CONFIG_SEED, POOL_SEED, RECEIPT_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CreateReceiptMint<'info> {
+    // only the authority of the config can change the pool, it pays the receipt mint
+    #[account(
+        mut,
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+        // a liquid pool already represents positions with its shares
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    // receipts have the decimals of the stake mint
+    pub stake_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    /// CHECK: created and initialized below, the extensions depend on the receipt mode
+    /// a receipt mint left by a closed pool at the same address is checked below and reused
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateReceiptMint<'info> {
+    pub fn handler(
+        &mut self,
+        receipt_mode: ReceiptMode,
+        bumps: CreateReceiptMintBumps,
+    ) -> Result<()> {
+        require!(
+            receipt_mode != ReceiptMode::None,
+            MyErrorCode::InvalidReceiptMode
+        );
+        require!(
+            self.pool.receipt_mode == ReceiptMode::None,
+            MyErrorCode::ReceiptMintAlreadySet
+        );
+        // positions opened before would have no receipt to burn on unstake
+        require_eq!(self.pool.total_staked, 0, MyErrorCode::PoolNotEmpty);
+
+        if self.receipt_mint.data_is_empty() {
+            self.create_mint(receipt_mode, bumps)?;
+        } else {
+            self.check_closed_pool_mint(receipt_mode)?;
+        }
+
+        self.pool.receipt_mode = receipt_mode;
+        self.pool.receipt_mint = self.receipt_mint.key();
+        Ok(())
+    }
+
+    fn create_mint(
+        &mut self,
+        receipt_mode: ReceiptMode,
+        bumps: CreateReceiptMintBumps,
+    ) -> Result<()> {
+        let extensions = match receipt_mode {
+            ReceiptMode::NonTransferable => vec![ExtensionType::NonTransferable],
+            _ => vec![],
+        };
+        let size = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+        let lamports = Rent::get()?.minimum_balance(size);
+
+        // the receipt mint is a PDA of the pool, so it signs its own creation
+        let pool_key = self.pool.key();
+        let seeds = &[RECEIPT_SEED, pool_key.as_ref(), &[bumps.receipt_mint]];
+        let signer_seeds = &[&seeds[..]];
+
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: self.signer.to_account_info(),
+                    to: self.receipt_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            size.try_into().unwrap(),
+            &spl_token_2022::ID,
+        )?;
+
+        // extensions must be initialized before the mint
+        if receipt_mode == ReceiptMode::NonTransferable {
+            non_transferable_mint_initialize(CpiContext::new(
+                self.token_program.to_account_info(),
+                NonTransferableMintInitialize {
+                    token_program_id: self.token_program.to_account_info(),
+                    mint: self.receipt_mint.to_account_info(),
+                },
+            ))?;
+        }
+
+        // the pool mints receipts on stake
+        initialize_mint2(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                InitializeMint2 {
+                    mint: self.receipt_mint.to_account_info(),
+                },
+            ),
+            self.stake_mint.decimals,
+            &self.pool.key(),
+            None,
+        )
+    }
+
+    // every receipt of the closed pool was burned when its stake left, the mint only has to fit the mode
+    fn check_closed_pool_mint(&self, receipt_mode: ReceiptMode) -> Result<()> {
+        require_keys_eq!(
+            *self.receipt_mint.owner,
+            spl_token_2022::ID,
+            MyErrorCode::InvalidReceiptMode
+        );
+        let data = self.receipt_mint.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+        require!(
+            Option::<Pubkey>::from(mint.base.mint_authority) == Some(self.pool.key()),
+            MyErrorCode::InvalidReceiptMode
+        );
+        require_eq!(mint.base.supply, 0, MyErrorCode::PoolNotEmpty);
+        let non_transferable = mint.get_extension::<NonTransferable>().is_ok();
+        require!(
+            non_transferable == (receipt_mode == ReceiptMode::NonTransferable),
+            MyErrorCode::InvalidReceiptMode
+        );
+        Ok(())
+    }
+}
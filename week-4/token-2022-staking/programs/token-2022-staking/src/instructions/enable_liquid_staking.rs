@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_2022::Token2022, token_interface::Mint};
+
+use crate::{
+    error::MyErrorCode, Config, Pool, ReceiptMode, StakeMode, CONFIG_SEED, POOL_SEED, SHARE_SEED,
+};
+
+#[derive(Accounts)]
+pub struct EnableLiquidStaking<'info> {
+    // only the authority of the config can change the pool, it pays the share mint
+    #[account(
+        mut,
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+    )]
+    pub pool: Account<'info, Pool>,
+    // shares have the decimals of the stake mint
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    // the pool mints shares on liquid_stake
+    // init_if_needed to reuse the share mint left by a closed pool at the same address
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [SHARE_SEED, pool.key().as_ref()],
+        bump,
+        mint::decimals = stake_mint.decimals,
+        mint::authority = pool,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> EnableLiquidStaking<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        require!(
+            self.pool.stake_mode == StakeMode::Position,
+            MyErrorCode::InvalidStakeMode
+        );
+        // rewards compound into the staked amount, they must be the same token
+        require_keys_eq!(
+            self.pool.reward_mint,
+            self.pool.stake_mint,
+            MyErrorCode::LiquidRewardMintMismatch
+        );
+        // positions opened before would have no share
+        require_eq!(self.pool.total_staked, 0, MyErrorCode::PoolNotEmpty);
+        // shares left over from a closed pool would claim part of the new stake
+        require_eq!(self.share_mint.supply, 0, MyErrorCode::PoolNotEmpty);
+        // extra rewards, receipts and unbonding are bound to a StakeInfo
+        require!(
+            self.pool.extra_rewards.is_empty()
+                && self.pool.receipt_mode == ReceiptMode::None
+                && self.pool.unbonding_period == 0,
+            MyErrorCode::InvalidStakeMode
+        );
+
+        // settle the period without stakers before switching
+        self.pool.update(&Clock::get()?)?;
+        self.pool.stake_mode = StakeMode::Liquid;
+        self.pool.share_mint = self.share_mint.key();
+        Ok(())
+    }
+}
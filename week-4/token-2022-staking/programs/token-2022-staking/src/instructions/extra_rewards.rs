@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TransferChecked,
+};
+
+use crate::{error::MyErrorCode, Pool};
+
+// remaining_accounts expected for each extra reward of the pool, in the order of pool.extra_rewards:
+// [reward mint, reward_ata of the pool, token account receiving the reward, reward token program]
+pub const EXTRA_REWARD_ACCOUNTS: usize = 4;
+
+// split remaining_accounts into the accounts of the extra rewards of the pool and the accounts after them
+pub fn split_extra_reward_accounts<'a, 'info>(
+    pool: &Pool,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+    let len = pool.extra_rewards.len() * EXTRA_REWARD_ACCOUNTS;
+    require_gte!(
+        remaining_accounts.len(),
+        len,
+        MyErrorCode::InvalidExtraRewardAccounts
+    );
+    Ok(remaining_accounts.split_at(len))
+}
+
+// transfer `amounts[i]` of the extra reward `i` of the pool to a token account owned by `recipient`
+// every account passed in remaining_accounts is checked against the pool before any transfer
+pub fn transfer_extra_rewards<'info>(
+    pool: &Account<'info, Pool>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amounts: &[u64],
+    recipient: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require_eq!(
+        remaining_accounts.len(),
+        pool.extra_rewards.len() * EXTRA_REWARD_ACCOUNTS,
+        MyErrorCode::InvalidExtraRewardAccounts
+    );
+
+    for ((extra_reward, accounts), amount) in pool
+        .extra_rewards
+        .iter()
+        .zip(remaining_accounts.chunks(EXTRA_REWARD_ACCOUNTS))
+        .zip(amounts.iter())
+    {
+        let [reward_mint, reward_ata, destination, token_program] = accounts else {
+            return err!(MyErrorCode::InvalidExtraRewardAccounts);
+        };
+
+        // the mint, the pool account and the token program are the ones registered in the pool
+        require_keys_eq!(
+            reward_mint.key(),
+            extra_reward.mint,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            reward_ata.key(),
+            extra_reward.reward_ata,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            token_program.key(),
+            extra_reward.token_program,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            *reward_mint.owner,
+            extra_reward.token_program,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            *destination.owner,
+            extra_reward.token_program,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+
+        // the destination must hold the reward mint and belong to the recipient
+        let destination_account =
+            TokenAccount::try_deserialize(&mut &destination.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            destination_account.mint,
+            extra_reward.mint,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            destination_account.owner,
+            *recipient,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        // fail with a clear error instead of a failed token transfer
+        let reward_ata_account =
+            TokenAccount::try_deserialize(&mut &reward_ata.try_borrow_data()?[..])?;
+        require_gte!(
+            reward_ata_account.amount,
+            *amount,
+            MyErrorCode::InsufficientRewardBalance
+        );
+        let decimals = Mint::try_deserialize(&mut &reward_mint.try_borrow_data()?[..])?.decimals;
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: reward_ata.clone(),
+                    to: destination.clone(),
+                    authority: pool.to_account_info(),
+                    mint: reward_mint.clone(),
+                },
+                signer_seeds,
+            ),
+            *amount,
+            decimals,
+        )?;
+    }
+
+    Ok(())
+}
+
+// balance of the reward_ata of every extra reward of the pool, from the same remaining_accounts layout
+pub fn extra_reward_balances(pool: &Pool, remaining_accounts: &[AccountInfo]) -> Result<Vec<u64>> {
+    require_eq!(
+        remaining_accounts.len(),
+        pool.extra_rewards.len() * EXTRA_REWARD_ACCOUNTS,
+        MyErrorCode::InvalidExtraRewardAccounts
+    );
+
+    pool.extra_rewards
+        .iter()
+        .zip(remaining_accounts.chunks(EXTRA_REWARD_ACCOUNTS))
+        .map(|(extra_reward, accounts)| {
+            let reward_ata = &accounts[1];
+            require_keys_eq!(
+                reward_ata.key(),
+                extra_reward.reward_ata,
+                MyErrorCode::InvalidExtraRewardAccounts
+            );
+            Ok(TokenAccount::try_deserialize(&mut &reward_ata.try_borrow_data()?[..])?.amount)
+        })
+        .collect()
+}
+
+// close the reward_ata of every extra reward of the pool, they must be empty
+pub fn close_extra_reward_atas<'info>(
+    pool: &Account<'info, Pool>,
+    remaining_accounts: &[AccountInfo<'info>],
+    destination: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for (extra_reward, accounts) in pool
+        .extra_rewards
+        .iter()
+        .zip(remaining_accounts.chunks(EXTRA_REWARD_ACCOUNTS))
+    {
+        let (reward_ata, token_program) = (&accounts[1], &accounts[3]);
+        require_keys_eq!(
+            reward_ata.key(),
+            extra_reward.reward_ata,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+        require_keys_eq!(
+            token_program.key(),
+            extra_reward.token_program,
+            MyErrorCode::InvalidExtraRewardAccounts
+        );
+
+        close_account(CpiContext::new_with_signer(
+            token_program.clone(),
+            CloseAccount {
+                account: reward_ata.clone(),
+                destination: destination.clone(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    Ok(())
+}
@@ -7,24 +7,34 @@ use anchor_spl::{
     },
     token_2022_extensions::{self},
     token_interface::{
-        spl_token_metadata_interface::state::TokenMetadata, TokenMetadataInitialize,
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        TokenMetadataInitialize, TokenMetadataUpdateField,
     },
 };
 
-use crate::{Config, CONFIG_SEED, MINT_NAME, MINT_SYMBOL, MINT_URI};
+use crate::{Config, CONFIG_SEED};
 
+// an entry of the additional_metadata of the reward mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MetadataField {
+    pub key: String,
+    pub value: String,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(id: u64)]
 pub struct Initialize<'info> {
     // account that signs the transaction
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    // account save the config of the program
+    // account save the config of the program, one per id
     #[account(
         init,
         payer = signer,
         space = 8 + Config::INIT_SPACE,
-        seeds = [CONFIG_SEED],
+        seeds = [CONFIG_SEED, id.to_le_bytes().as_ref()],
         bump
     )]
     pub config: Account<'info, Config>,
@@ -38,18 +48,53 @@ pub struct Initialize<'info> {
 }
 
 impl<'info> Initialize<'info> {
-    pub fn handler(&mut self, bumps: InitializeBumps) -> Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn handler(
+        &mut self,
+        id: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        decimals: u8,
+        additional_metadata: Vec<MetadataField>,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
         self.config.set_inner(Config {
+            id,
             authority: self.signer.to_account_info().key(),
             reward_mint: self.mint.to_account_info().key(),
+            pool_count: 0,
         });
-        self.create_metadata(bumps)?;
+
+        // define the metadata of the token to be created
+        let metadata = TokenMetadata {
+            update_authority:
+                anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey(
+                    self.config.to_account_info().key(),
+                ),
+            mint: self.mint.to_account_info().key(),
+            name,
+            symbol,
+            uri,
+            additional_metadata: additional_metadata
+                .into_iter()
+                .map(|field| (field.key, field.value))
+                .collect(),
+        };
+
+        self.create_metadata(metadata, decimals, bumps)?;
         Ok(())
     }
 
-    pub fn create_metadata(&mut self, bumps: InitializeBumps) -> Result<()> {
+    pub fn create_metadata(
+        &mut self,
+        metadata: TokenMetadata,
+        decimals: u8,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
         // acquire the seeds to sign the transaction init token metadata
-        let seeds = &[CONFIG_SEED, &[bumps.config]];
+        let id = self.config.id.to_le_bytes();
+        let seeds = &[CONFIG_SEED, id.as_ref(), &[bumps.config]];
         let signer_seeds = &[&seeds[..]];
 
         // calculate the size of the account, because we use extension MetadataPointer, account size is different legacy spl-token account
@@ -57,20 +102,8 @@ impl<'info> Initialize<'info> {
             ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
                 .unwrap();
 
-        // define the metadata of the token to be created (for simplicity, i hardcode the metadata and empty additional_metadata)
-        let metadata = TokenMetadata {
-            update_authority:
-                anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey(
-                    self.config.to_account_info().key(),
-                ),
-            mint: self.mint.to_account_info().key(),
-            name: MINT_NAME.to_string(),
-            symbol: MINT_SYMBOL.to_string(),
-            uri: MINT_URI.to_string(),
-            additional_metadata: vec![],
-        };
-
         // calculate the extra space needed for the metadata by Type-length-value of the metadata
+        // additional_metadata is included so the account is funded for every field
         let extension_extra_space = metadata.tlv_size_of().unwrap();
 
         // calculate the minimum balance needed for the account
@@ -113,7 +146,7 @@ impl<'info> Initialize<'info> {
                     mint: self.mint.to_account_info(),
                 },
             ),
-            9,
+            decimals,
             &self.config.to_account_info().key(),
             None,
         )?;
@@ -132,11 +165,29 @@ impl<'info> Initialize<'info> {
                 },
                 signer_seeds,
             ),
-            MINT_NAME.to_string(),
-            MINT_SYMBOL.to_string(),
-            MINT_URI.to_string(),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
         )?;
 
+        // token_metadata_initialize only writes name, symbol and uri
+        // each additional field is added with an update of the metadata
+        for (key, value) in metadata.additional_metadata {
+            token_2022_extensions::token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TokenMetadataUpdateField {
+                        token_program_id: self.token_program.to_account_info(),
+                        metadata: self.mint.to_account_info(),
+                        update_authority: self.config.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                Field::Key(key),
+                value,
+            )?;
+        }
+
         Ok(())
     }
 }
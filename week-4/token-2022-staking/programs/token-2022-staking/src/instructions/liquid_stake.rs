@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::MyErrorCode, transfer_checked_with_hook, Pool, StakeMode, POOL_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LiquidStake<'info> {
+    // signer is the staker
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+        constraint = !pool.paused @MyErrorCode::PoolPaused,
+        constraint = pool.stake_mode == StakeMode::Liquid @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = pool.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // the reward_ata holds both the staked tokens and the undistributed reward
+    #[account(
+        mut,
+        address = pool.reward_ata,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = share_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = share_token_program,
+    )]
+    pub staker_share_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub share_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LiquidStake<'info> {
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    // returns the amount received by the pool and the shares minted for it
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &LiquidStakeBumps,
+    ) -> Result<(u64, u64)> {
+        require_gt!(amount, 0, MyErrorCode::InvalidDepositAmount);
+        // compound the reward up to now, new shares are priced after it
+        self.pool.update(&Clock::get()?)?;
+
+        // a transfer fee may be withheld, only the amount received is staked
+        let received = self.deposit(amount, remaining_accounts)?;
+        let shares = self.pool.shares_for(received, self.share_mint.supply)?;
+        require_gt!(shares, 0, MyErrorCode::ZeroShares);
+
+        self.pool.total_staked = self
+            .pool
+            .total_staked
+            .checked_add(received)
+            .ok_or(MyErrorCode::Overflow)?;
+        self.mint_shares(shares, bumps)?;
+        Ok((received, shares))
+    }
+
+    // returns the amount received by the reward_ata
+    fn deposit(&mut self, amount: u64, hook_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let balance_before = self.reward_ata.amount;
+
+        transfer_checked_with_hook(
+            CpiContext::new(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.staker_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                    to: self.reward_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
+
+        self.reward_ata.reload()?;
+        Ok(self
+            .reward_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?)
+    }
+
+    fn mint_shares(&mut self, shares: u64, bumps: &LiquidStakeBumps) -> Result<()> {
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.share_token_program.to_account_info(),
+                MintTo {
+                    mint: self.share_mint.to_account_info(),
+                    to: self.staker_share_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::MyErrorCode, transfer_checked_with_hook, Pool, StakeMode, POOL_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LiquidUnstake<'info> {
+    // signer is the holder of the shares
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+        constraint = pool.stake_mode == StakeMode::Liquid @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = pool.share_mint,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = pool.reward_ata,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = share_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = share_token_program,
+    )]
+    pub staker_share_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub share_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> LiquidUnstake<'info> {
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    // returns the amount redeemed for the shares
+    pub fn handler(
+        &mut self,
+        shares: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &LiquidUnstakeBumps,
+    ) -> Result<u64> {
+        require_gt!(shares, 0, MyErrorCode::InvalidUnstakeAmount);
+        require_gte!(
+            self.staker_share_ata.amount,
+            shares,
+            MyErrorCode::InsufficientStakeAmount
+        );
+        // compound the reward up to now, the shares are redeemed after it
+        self.pool.update(&Clock::get()?)?;
+
+        let amount = self.pool.underlying_for(shares, self.share_mint.supply)?;
+        require_gt!(amount, 0, MyErrorCode::ZeroShares);
+        // fail with a clear error instead of a failed token transfer
+        require_gte!(
+            self.reward_ata.amount,
+            amount,
+            MyErrorCode::InsufficientRewardBalance
+        );
+
+        self.pool.total_staked = self
+            .pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        self.burn_shares(shares)?;
+        self.back_to_staker(amount, remaining_accounts, bumps)?;
+        Ok(amount)
+    }
+
+    fn burn_shares(&mut self, shares: u64) -> Result<()> {
+        burn(
+            CpiContext::new(
+                self.share_token_program.to_account_info(),
+                Burn {
+                    mint: self.share_mint.to_account_info(),
+                    from: self.staker_share_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        Ok(())
+    }
+
+    fn back_to_staker(
+        &mut self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        bumps: &LiquidUnstakeBumps,
+    ) -> Result<()> {
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.reward_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                    to: self.staker_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, transfer_hook::TransferHook,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::AccountState,
+};
+
+use crate::error::MyErrorCode;
+
+// reject mints whose extensions would let the tokens of the pool be locked or taken from it
+// the same checks hold for the stake mint and every reward mint, `error` tells which one failed
+pub fn check_mint_extensions(mint: &AccountInfo, error: MyErrorCode) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    // tokens that can't be transferred could never be paid out of the pool
+    if mint.get_extension::<NonTransferable>().is_ok() {
+        msg!("Mint is non-transferable");
+        return Err(error.into());
+    }
+    // a permanent delegate could move the tokens out of the accounts of the pool
+    if let Ok(permanent_delegate) = mint.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(permanent_delegate.delegate).is_some() {
+            msg!("Mint has a permanent delegate");
+            return Err(error.into());
+        }
+    }
+    // the token accounts created by the program would be frozen
+    if let Ok(default_account_state) = mint.get_extension::<DefaultAccountState>() {
+        if default_account_state.state == AccountState::Frozen as u8 {
+            msg!("Mint accounts are frozen by default");
+            return Err(error.into());
+        }
+    }
+
+    Ok(())
+}
+
+// reward transfers carry no transfer hook accounts, a hooked reward mint would fail every payout
+// and with it the unstake paying the reward
+pub fn check_reward_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    check_mint_extensions(mint, MyErrorCode::UnsupportedRewardMintExtension)?;
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    if let Ok(transfer_hook) = mint.get_extension::<TransferHook>() {
+        if Option::<Pubkey>::from(transfer_hook.program_id).is_some() {
+            msg!("Reward mint has a transfer hook");
+            return err!(MyErrorCode::UnsupportedRewardMintExtension);
+        }
+    }
+
+    Ok(())
+}
@@ -13,3 +13,115 @@ pub use unstake::*;
 pub mod create;
 
 pub use create::*;
+
+pub mod claim;
+
+pub use claim::*;
+
+pub mod set_reward_rate;
+
+pub use set_reward_rate::*;
+
+pub mod top_up;
+
+pub use top_up::*;
+
+pub mod set_pool_paused;
+
+pub use set_pool_paused::*;
+
+pub mod close_pool;
+
+pub use close_pool::*;
+
+pub mod update_metadata;
+
+pub use update_metadata::*;
+
+pub mod extra_rewards;
+
+pub use extra_rewards::*;
+
+pub mod reward;
+
+pub use reward::*;
+
+pub mod add_extra_reward;
+
+pub use add_extra_reward::*;
+
+pub mod transfer_hook;
+
+pub use transfer_hook::*;
+
+pub mod create_receipt_mint;
+
+pub use create_receipt_mint::*;
+
+pub mod enable_liquid_staking;
+
+pub use enable_liquid_staking::*;
+
+pub mod liquid_stake;
+
+pub use liquid_stake::*;
+
+pub mod liquid_unstake;
+
+pub use liquid_unstake::*;
+
+pub mod transfer_fee;
+
+pub use transfer_fee::*;
+
+pub mod mint_extensions;
+
+pub use mint_extensions::*;
+
+pub mod set_unbonding_period;
+
+pub use set_unbonding_period::*;
+
+pub mod request_unstake;
+
+pub use request_unstake::*;
+
+pub mod close_position;
+
+pub use close_position::*;
+
+pub mod withdraw_unbonded;
+
+pub use withdraw_unbonded::*;
+
+pub mod stake_for;
+
+pub use stake_for::*;
+
+pub mod set_claim_delegate;
+
+pub use set_claim_delegate::*;
+
+pub mod set_vesting_schedule;
+
+pub use set_vesting_schedule::*;
+
+pub mod release_vested;
+
+pub use release_vested::*;
+
+pub mod pending_reward;
+
+pub use pending_reward::*;
+
+pub mod pool_info;
+
+pub use pool_info::*;
+
+pub mod set_governance;
+
+pub use set_governance::*;
+
+pub mod update_voter_weight_record;
+
+pub use update_voter_weight_record::*;
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Pool, StakeInfo, StakeMode, STAKEINFO_SEED};
+
+// returned by pending_reward
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PendingReward {
+    pub staked: u64,
+    // reward of reward_mint the staker would get by claiming now
+    pub reward: u64,
+    // reward of every extra reward, in the order of pool.extra_rewards
+    pub extra_rewards: Vec<u64>,
+}
+
+#[derive(Accounts)]
+pub struct GetPendingReward<'info> {
+    #[account(
+      constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: only used as a seed of the stake_info
+    pub staker: UncheckedAccount<'info>,
+    #[account(
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), staker.key().as_ref()],
+      bump,
+      constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+impl<'info> GetPendingReward<'info> {
+    // read only, the accounts are updated on copies
+    pub fn handler(&self) -> Result<PendingReward> {
+        let mut pool = (*self.pool).clone();
+        pool.update(&Clock::get()?)?;
+        let mut stake_info = (*self.stake_info).clone();
+        stake_info.settle_reward(&mut pool)?;
+
+        Ok(PendingReward {
+            staked: stake_info.amount,
+            reward: stake_info.reward,
+            extra_rewards: stake_info
+                .extra_rewards
+                .iter()
+                .map(|extra| extra.reward)
+                .collect(),
+        })
+    }
+}
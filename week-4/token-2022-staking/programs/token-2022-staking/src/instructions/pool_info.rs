@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{EmissionMode, Pool, StakeMode};
+
+// emission of an extra reward, returned by pool_info
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ExtraRewardInfo {
+    pub mint: Pubkey,
+    pub reward_rate: u64,
+    pub remaining_allocation: u64,
+}
+
+// returned by pool_info, everything a client needs to show the pool and its APR
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolInfo {
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub stake_mode: StakeMode,
+    pub paused: bool,
+    // in liquid mode it includes the compounded reward
+    pub total_staked: u64,
+    // stakers holding a stake, 0 in liquid mode
+    pub staker_count: u64,
+    pub distributed: u64,
+    pub remaining_allocation: u64,
+    // reward emitted per unit of emission_mode, split among total_staked
+    pub reward_rate: u64,
+    pub emission_mode: EmissionMode,
+    // current position of the emission clock, no reward is emitted once it reaches emission_end
+    pub now: u64,
+    pub emission_end: u64,
+    pub extra_rewards: Vec<ExtraRewardInfo>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolInfo<'info> {
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> GetPoolInfo<'info> {
+    // read only, the pool is updated on a copy
+    pub fn handler(&self) -> Result<PoolInfo> {
+        let clock = Clock::get()?;
+        let mut pool = (*self.pool).clone();
+        pool.update(&clock)?;
+
+        Ok(PoolInfo {
+            stake_mint: pool.stake_mint,
+            reward_mint: pool.reward_mint,
+            stake_mode: pool.stake_mode,
+            paused: pool.paused,
+            total_staked: pool.total_staked,
+            staker_count: pool.staker_count,
+            distributed: pool.distributed,
+            remaining_allocation: pool.remaining_allocation(),
+            reward_rate: pool.reward_rate,
+            emission_mode: pool.emission_mode,
+            now: pool.now(&clock),
+            emission_end: pool.emission_end,
+            extra_rewards: pool
+                .extra_rewards
+                .iter()
+                .map(|extra_reward| ExtraRewardInfo {
+                    mint: extra_reward.mint,
+                    reward_rate: extra_reward.reward_rate,
+                    remaining_allocation: extra_reward.remaining_allocation(),
+                })
+                .collect(),
+        })
+    }
+}
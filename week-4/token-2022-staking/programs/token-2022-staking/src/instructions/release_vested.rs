@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::MyErrorCode, Vesting, VESTING_SEED};
+
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    // signer is the staker
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // the pool is not needed, vested reward can be released after the pool is closed
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, vesting.pool.as_ref(), signer.key().as_ref()],
+        bump,
+        has_one = reward_mint @MyErrorCode::InvalidRewardMintAccount,
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+    #[account(
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub vesting_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // associated token account of the staker to receive reward token
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub staker_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseVested<'info> {
+    pub fn handler(&mut self, bumps: &ReleaseVestedBumps) -> Result<()> {
+        let amount = self.vesting.release(Clock::get()?.unix_timestamp)?;
+        require_gt!(amount, 0, MyErrorCode::NothingToRelease);
+
+        let seeds = &[
+            VESTING_SEED,
+            self.vesting.pool.as_ref(),
+            self.vesting.staker.as_ref(),
+            &[bumps.vesting],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vesting_ata.to_account_info(),
+                    to: self.staker_reward_ata.to_account_info(),
+                    authority: self.vesting.to_account_info(),
+                    mint: self.reward_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            self.reward_mint.decimals,
+        )?;
+        Ok(())
+    }
+}
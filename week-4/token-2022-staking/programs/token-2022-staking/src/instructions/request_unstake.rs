@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::MyErrorCode, transfer_checked_with_hook, Pool, ReceiptMode, StakeInfo, StakeMode,
+    UnbondingTicket, POOL_SEED, STAKEINFO_SEED, UNBONDING_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct RequestUnstake<'info> {
+    // signer is the staker, it pays the ticket
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()],
+        bump,
+        constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = stake_info,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub stake_info_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // the id is chosen by the staker, so several tickets can be open at the same time
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + UnbondingTicket::INIT_SPACE,
+        seeds = [UNBONDING_SEED, pool.key().as_ref(), signer.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+    // holds the unbonding tokens until withdraw_unbonded
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = stake_mint,
+        associated_token::authority = ticket,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub ticket_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // receipt accounts, only required when the pool mints receipts
+    #[account(
+        mut,
+        address = pool.receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = receipt_token_program,
+    )]
+    pub staker_receipt_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestUnstake<'info> {
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    // returns the amount moved into unbonding, net of transfer fees
+    pub fn handler(
+        &mut self,
+        id: u64,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &RequestUnstakeBumps,
+    ) -> Result<u64> {
+        require_gt!(amount, 0, MyErrorCode::InvalidUnstakeAmount);
+        require_gte!(
+            self.stake_info.amount,
+            amount,
+            MyErrorCode::InsufficientStakeAmount
+        );
+        let clock = Clock::get()?;
+        self.stake_info.check_vote_lock(clock.unix_timestamp)?;
+        // the receipts of the unbonding amount are burned first
+        self.burn_receipt(amount)?;
+
+        // the amount stops earning now, the reward stays in the stake info to be claimed
+        self.stake_info.unbond(&mut self.pool, amount, &clock)?;
+
+        // a transfer fee may be withheld, the ticket holds what it received
+        let received = self.move_to_ticket(amount, remaining_accounts, bumps)?;
+        let unlock_at = i64::try_from(self.pool.unbonding_period)
+            .ok()
+            .and_then(|period| clock.unix_timestamp.checked_add(period))
+            .ok_or(MyErrorCode::Overflow)?;
+
+        self.ticket.set_inner(UnbondingTicket {
+            pool: self.pool.key(),
+            staker: self.signer.key(),
+            stake_mint: self.stake_mint.key(),
+            id,
+            amount: received,
+            unlock_at,
+        });
+        // the stake info stays open until the ticket is withdrawn
+        self.stake_info.unbonding_count = self
+            .stake_info
+            .unbonding_count
+            .checked_add(1)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(received)
+    }
+
+    fn burn_receipt(&mut self, amount: u64) -> Result<()> {
+        if self.pool.receipt_mode == ReceiptMode::None {
+            return Ok(());
+        }
+        let (Some(receipt_mint), Some(staker_receipt_ata), Some(receipt_token_program)) = (
+            &self.receipt_mint,
+            &self.staker_receipt_ata,
+            &self.receipt_token_program,
+        ) else {
+            return err!(MyErrorCode::MissingReceiptAccounts);
+        };
+
+        burn(
+            CpiContext::new(
+                receipt_token_program.to_account_info(),
+                Burn {
+                    mint: receipt_mint.to_account_info(),
+                    from: staker_receipt_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // returns the amount received by the ticket_ata
+    fn move_to_ticket(
+        &mut self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        bumps: &RequestUnstakeBumps,
+    ) -> Result<u64> {
+        let pool_key = self.pool.key();
+        let signer_key = self.signer.key();
+        let seeds = &[
+            STAKEINFO_SEED,
+            pool_key.as_ref(),
+            signer_key.as_ref(),
+            &[bumps.stake_info],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.stake_info_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                    to: self.ticket_ata.to_account_info(),
+                    authority: self.stake_info.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
+
+        // the ticket_ata was just created, so its balance is the received amount
+        self.ticket_ata.reload()?;
+        Ok(self.ticket_ata.amount)
+    }
+}
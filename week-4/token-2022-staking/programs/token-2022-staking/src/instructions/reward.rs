@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{error::MyErrorCode, Pool, Vesting};
+
+// accounts paying the reward of reward_mint out of the reward_ata of the pool
+pub struct RewardAccounts<'a, 'info> {
+    pub pool: &'a Account<'info, Pool>,
+    // owner of the stake_info, the reward always goes to it
+    pub staker: Pubkey,
+    pub reward_mint: &'a InterfaceAccount<'info, Mint>,
+    pub reward_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    pub staker_reward_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    // only required when the pool vests the reward
+    pub vesting: Option<&'a mut Account<'info, Vesting>>,
+    pub vesting_ata: Option<&'a mut InterfaceAccount<'info, TokenAccount>>,
+    pub reward_token_program: &'a Interface<'info, TokenInterface>,
+}
+
+// pay `amount` to the staker, or lock it in its vesting account when the pool vests the reward
+pub fn pay_reward(
+    mut accounts: RewardAccounts,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if accounts.pool.vesting_duration == 0 {
+        return transfer_reward(&accounts, accounts.staker_reward_ata, amount, signer_seeds);
+    }
+    if amount == 0 {
+        return Ok(());
+    }
+    let (Some(vesting), Some(vesting_ata)) = (accounts.vesting.take(), accounts.vesting_ata.take())
+    else {
+        return err!(MyErrorCode::MissingVestingAccounts);
+    };
+    // a pool re-created at the same address may reward another mint, the old reward must be released first
+    if !vesting.is_empty() {
+        require_keys_eq!(
+            vesting.reward_mint,
+            accounts.reward_mint.key(),
+            MyErrorCode::InvalidRewardMintAccount
+        );
+    }
+    let balance_before = vesting_ata.amount;
+    transfer_reward(&accounts, vesting_ata, amount, signer_seeds)?;
+
+    // a transfer fee may be withheld, only the amount received vests
+    vesting_ata.reload()?;
+    let received = vesting_ata
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(MyErrorCode::Overflow)?;
+    vesting.pool = accounts.pool.key();
+    vesting.staker = accounts.staker;
+    vesting.reward_mint = accounts.reward_mint.key();
+    vesting.add(
+        received,
+        Clock::get()?.unix_timestamp,
+        accounts.pool.vesting_cliff,
+        accounts.pool.vesting_duration,
+    )
+}
+
+fn transfer_reward<'info>(
+    accounts: &RewardAccounts<'_, 'info>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // fail with a clear error instead of a failed token transfer
+    require_gte!(
+        accounts.reward_ata.amount,
+        amount,
+        MyErrorCode::InsufficientRewardBalance
+    );
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.reward_token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.reward_ata.to_account_info(),
+                to: to.to_account_info(),
+                authority: accounts.pool.to_account_info(),
+                mint: accounts.reward_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        accounts.reward_mint.decimals,
+    )
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Pool, StakeInfo, STAKEINFO_SEED};
+
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    // only the staker can choose who claims for it
+    pub signer: Signer<'info>,
+    pub pool: Account<'info, Pool>,
+    #[account(
+      mut,
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()],
+      bump,
+      constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+impl<'info> SetClaimDelegate<'info> {
+    // the delegate can only send the reward to the staker, Pubkey::default() removes it
+    pub fn handler(&mut self, delegate: Pubkey) -> Result<()> {
+        self.stake_info.claim_delegate = delegate;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Config, Pool, StakeMode, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct SetGovernance<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        // the voter weight is read from the StakeInfo
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> SetGovernance<'info> {
+    // the realm must use this program as its voter weight addin for the governing mint
+    // Pubkey::default() as realm disables the voter weight records of the pool
+    // vote_lock_period must cover the voting time of the realm
+    pub fn handler(
+        &mut self,
+        realm: Pubkey,
+        governing_token_mint: Pubkey,
+        vote_lock_period: u64,
+    ) -> Result<()> {
+        if realm != Pubkey::default() {
+            require_gt!(vote_lock_period, 0, MyErrorCode::InvalidVoteLockPeriod);
+        }
+        self.pool.governance_realm = realm;
+        self.pool.governing_token_mint = governing_token_mint;
+        self.pool.vote_lock_period = vote_lock_period;
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{Config, Pool, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct SetPoolPaused<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> SetPoolPaused<'info> {
+    pub fn handler(&mut self, paused: bool) -> Result<()> {
+        // settle the reward accrued before the pause state changes
+        // nothing accrues while the pool is paused
+        self.pool.update(&Clock::get()?)?;
+        self.pool.paused = paused;
+        Ok(())
+    }
+}
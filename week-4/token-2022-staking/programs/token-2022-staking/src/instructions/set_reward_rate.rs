@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Config, Pool, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct SetRewardRate<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> SetRewardRate<'info> {
+    pub fn handler(&mut self, reward_rate: u64) -> Result<()> {
+        require_gt!(reward_rate, 0, MyErrorCode::RewardRateMustBeGreaterThanZero);
+        // settle the reward accrued with the old rate before changing it
+        self.pool.update(&Clock::get()?)?;
+        self.pool.reward_rate = reward_rate;
+        Ok(())
+    }
+}
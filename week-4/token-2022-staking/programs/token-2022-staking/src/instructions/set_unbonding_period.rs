@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Config, Pool, StakeMode, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct SetUnbondingPeriod<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        // liquid shares are redeemed without a StakeInfo, they can't be unbonded
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> SetUnbondingPeriod<'info> {
+    // tickets requested before keep the unlock_at they were created with
+    pub fn handler(&mut self, unbonding_period: u64) -> Result<()> {
+        self.pool.unbonding_period = unbonding_period;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, Config, Pool, StakeMode, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct SetVestingSchedule<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump,
+        // liquid rewards compound into the shares, they are never claimed
+        constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+impl<'info> SetVestingSchedule<'info> {
+    // reward already vesting keeps its schedule until the staker claims again
+    pub fn handler(&mut self, cliff: u64, duration: u64) -> Result<()> {
+        require_gte!(duration, cliff, MyErrorCode::InvalidVestingSchedule);
+        self.pool.vesting_cliff = cliff;
+        self.pool.vesting_duration = duration;
+        Ok(())
+    }
+}
@@ -1,10 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface,transfer_checked, TransferChecked}
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::{error::MyErrorCode, Pool, StakeInfo, STAKEINFO_SEED};
+use crate::{
+    error::MyErrorCode, transfer_checked_with_hook, Pool, ReceiptMode, StakeInfo, StakeMode,
+    POOL_SEED, STAKEINFO_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Stake<'info> {
     // signer is the staker
@@ -13,7 +19,12 @@ pub struct Stake<'info> {
     // check pool account valid
     // use has_one to check if pool account has a field stake_mint = stake_mint account, if not, return InvalidStakeMintAccount error
     #[account(
+      mut,
+      seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
+      bump,
       has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+      constraint = !pool.paused @MyErrorCode::PoolPaused,
+      constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
     )]
     pub pool: Account<'info, Pool>,
     // stake_mint token program must be stake_token_program
@@ -25,7 +36,7 @@ pub struct Stake<'info> {
     #[account(
       init_if_needed,
       payer = signer,
-      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()], 
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()],
       space = 8 + StakeInfo::INIT_SPACE,
       bump
     )]
@@ -47,59 +58,117 @@ pub struct Stake<'info> {
       associated_token::token_program = stake_token_program,
     )]
     pub stake_info_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // receipt accounts, only required when the pool mints receipts
+    #[account(
+      mut,
+      address = pool.receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = receipt_mint,
+      associated_token::authority = signer,
+      associated_token::token_program = receipt_token_program,
+    )]
+    pub staker_receipt_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
     pub stake_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Stake<'info> {
-    pub fn handler(&mut self, amount: u64) -> Result<()> { 
-      // deposit amount to stake_info_ata
-      self.deposit(amount)?;
-      // update stake_info
-      self.update_stake_info(amount)?;
-      Ok(())
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    // returns the amount staked
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &StakeBumps,
+    ) -> Result<u64> {
+        // deposit amount to stake_info_ata
+        // a transfer fee may be withheld, only the amount received is staked
+        let received = self.deposit(amount, remaining_accounts)?;
+        // update stake_info
+        self.update_stake_info(received)?;
+        // one receipt per staked token
+        self.mint_receipt(received, bumps)?;
+        Ok(received)
     }
 
-    fn update_stake_info(&mut self, amount: u64) -> Result<()> {
-      // check if the staker has staked before
-      // if staker has staked before, calculate the reward and add the amount to the stake_info account
-      // then update the stake_info account
-      if self.stake_info.amount.gt(&0) {
-        self.stake_info.reward = self.pool.calculate_reward(self.stake_info.last_deposit_slot, Clock::get()?.slot);
-        self.stake_info.amount = self.stake_info.amount.checked_add(amount).ok_or(MyErrorCode::Overflow)?;
-      } else {
-        // else, set the amount and reward to the stake_info account
-        self.stake_info.amount = amount;
-        self.stake_info.reward = 0;
-      }
+    fn mint_receipt(&mut self, amount: u64, bumps: &StakeBumps) -> Result<()> {
+        if self.pool.receipt_mode == ReceiptMode::None {
+            return Ok(());
+        }
+        let (Some(receipt_mint), Some(staker_receipt_ata), Some(receipt_token_program)) = (
+            &self.receipt_mint,
+            &self.staker_receipt_ata,
+            &self.receipt_token_program,
+        ) else {
+            return err!(MyErrorCode::MissingReceiptAccounts);
+        };
 
-      self.stake_info.staker = self.signer.to_account_info().key();
-      // udate the last_deposit_slot to the current slot to keep track reward
-      self.stake_info.last_deposit_slot = Clock::get()?.slot;
-      Ok(())
-    }
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
 
-    fn deposit(&mut self, amount: u64) -> Result<()> {
-      require_gt!(amount, 0, MyErrorCode::InvalidDepositAmount);
-
-      // transfer token from staker_ata to stake_info_ata
-      transfer_checked(
-          CpiContext::new(
-            self.stake_token_program.to_account_info(), 
-            TransferChecked {
-              authority: self.signer.to_account_info(),
-              from: self.staker_ata.to_account_info(),
-              to: self.stake_info_ata.to_account_info(),
-              mint: self.stake_mint.to_account_info(),
-            }
-        ), 
-        amount, 
-        self.stake_mint.decimals
-      )?;
+        mint_to(
+            CpiContext::new_with_signer(
+                receipt_token_program.to_account_info(),
+                MintTo {
+                    mint: receipt_mint.to_account_info(),
+                    to: staker_receipt_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
 
-      Ok(())
+    fn update_stake_info(&mut self, amount: u64) -> Result<()> {
+        // settle the reward earned so far, then add the amount to the stake_info account
+        // the last_deposit_slot is updated to the current slot to keep track reward
+        self.stake_info
+            .deposit(&mut self.pool, amount, &Clock::get()?)?;
+        self.stake_info.staker = self.signer.to_account_info().key();
+        Ok(())
     }
 
+    // returns the amount received by the stake_info_ata
+    fn deposit(&mut self, amount: u64, hook_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        require_gt!(amount, 0, MyErrorCode::InvalidDepositAmount);
+        let balance_before = self.stake_info_ata.amount;
+
+        // transfer token from staker_ata to stake_info_ata
+        transfer_checked_with_hook(
+            CpiContext::new(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    authority: self.signer.to_account_info(),
+                    from: self.staker_ata.to_account_info(),
+                    to: self.stake_info_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
 
-}
\ No newline at end of file
+        self.stake_info_ata.reload()?;
+        let received = self
+            .stake_info_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?;
+        require_gt!(received, 0, MyErrorCode::InvalidDepositAmount);
+        Ok(received)
+    }
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::MyErrorCode, transfer_checked_with_hook, Pool, ReceiptMode, StakeInfo, StakeMode,
+    POOL_SEED, STAKEINFO_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeFor<'info> {
+    // signer funds the stake and pays the accounts, the position belongs to the beneficiary
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // owner of the position, it unstakes and claims as if it staked itself
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+      mut,
+      seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
+      bump,
+      has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+      constraint = !pool.paused @MyErrorCode::PoolPaused,
+      constraint = pool.stake_mode == StakeMode::Position @MyErrorCode::InvalidStakeMode,
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+      mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    // stake_info of the beneficiary, created by the signer if needed
+    #[account(
+      init_if_needed,
+      payer = signer,
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), beneficiary.key().as_ref()],
+      space = 8 + StakeInfo::INIT_SPACE,
+      bump
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+    // token account of the signer to transfer token from
+    #[account(
+      mut,
+      token::mint = stake_mint,
+      token::authority = signer,
+      token::token_program = stake_token_program,
+    )]
+    pub signer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = stake_mint,
+      associated_token::authority = stake_info,
+      associated_token::token_program = stake_token_program,
+    )]
+    pub stake_info_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // receipt accounts, only required when the pool mints receipts, the receipts go to the beneficiary
+    #[account(
+      mut,
+      address = pool.receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = receipt_mint,
+      associated_token::authority = beneficiary,
+      associated_token::token_program = receipt_token_program,
+    )]
+    pub beneficiary_receipt_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeFor<'info> {
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    // returns the amount staked
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &StakeForBumps,
+    ) -> Result<u64> {
+        // a transfer fee may be withheld, only the amount received is staked
+        let received = self.deposit(amount, remaining_accounts)?;
+        // settle the reward of the beneficiary, then add the amount to its stake_info
+        self.stake_info
+            .deposit(&mut self.pool, received, &Clock::get()?)?;
+        self.stake_info.staker = self.beneficiary.key();
+        // one receipt per staked token
+        self.mint_receipt(received, bumps)?;
+        Ok(received)
+    }
+
+    fn mint_receipt(&mut self, amount: u64, bumps: &StakeForBumps) -> Result<()> {
+        if self.pool.receipt_mode == ReceiptMode::None {
+            return Ok(());
+        }
+        let (Some(receipt_mint), Some(beneficiary_receipt_ata), Some(receipt_token_program)) = (
+            &self.receipt_mint,
+            &self.beneficiary_receipt_ata,
+            &self.receipt_token_program,
+        ) else {
+            return err!(MyErrorCode::MissingReceiptAccounts);
+        };
+
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.pool.stake_mint.as_ref(),
+            &[bumps.pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                receipt_token_program.to_account_info(),
+                MintTo {
+                    mint: receipt_mint.to_account_info(),
+                    to: beneficiary_receipt_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // returns the amount received by the stake_info_ata
+    fn deposit(&mut self, amount: u64, hook_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        require_gt!(amount, 0, MyErrorCode::InvalidDepositAmount);
+        let balance_before = self.stake_info_ata.amount;
+
+        transfer_checked_with_hook(
+            CpiContext::new(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    authority: self.signer.to_account_info(),
+                    from: self.signer_ata.to_account_info(),
+                    to: self.stake_info_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
+
+        self.stake_info_ata.reload()?;
+        let received = self
+            .stake_info_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?;
+        require_gt!(received, 0, MyErrorCode::InvalidDepositAmount);
+        Ok(received)
+    }
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{error::MyErrorCode, Config, Pool, RewardSource, CONFIG_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct TopUp<'info> {
+    // only the authority of the config can change the pool
+    #[account(
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, config.key().as_ref(), pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        address = pool.reward_mint,
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = pool.reward_ata,
+    )]
+    pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // token account of the signer to deposit from when the pool reward is deposited
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = signer,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_reward_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> TopUp<'info> {
+    pub fn handler(&mut self, amount: u64, bumps: TopUpBumps) -> Result<()> {
+        require_gt!(amount, 0, MyErrorCode::AllocationMustBeGreaterThanZero);
+        // settle the reward accrued with the old allocation, an exhausted pool accrues nothing until now
+        self.pool.update(&Clock::get()?)?;
+        let received = match self.pool.reward_source {
+            RewardSource::Minted => {
+                self.mint_reward(amount, bumps)?;
+                amount
+            }
+            RewardSource::Deposited => self.deposit_reward(amount)?,
+        };
+        self.pool.allocation = self
+            .pool
+            .allocation
+            .checked_add(received)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // returns the amount received by the pool, net of transfer fees
+    fn deposit_reward(&mut self, amount: u64) -> Result<u64> {
+        let funder_reward_ata = self
+            .funder_reward_ata
+            .as_ref()
+            .ok_or(MyErrorCode::MissingRewardFunder)?;
+        let balance_before = self.reward_ata.amount;
+
+        transfer_checked(
+            CpiContext::new(
+                self.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: funder_reward_ata.to_account_info(),
+                    to: self.reward_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                    mint: self.reward_mint.to_account_info(),
+                },
+            ),
+            amount,
+            self.reward_mint.decimals,
+        )?;
+
+        self.reward_ata.reload()?;
+        Ok(self
+            .reward_ata
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(MyErrorCode::Overflow)?)
+    }
+
+    fn mint_reward(&mut self, amount: u64, bumps: TopUpBumps) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_ata.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+
+        let id = self.config.id.to_le_bytes();
+        let seeds = &[CONFIG_SEED, id.as_ref(), &[bumps.config]];
+        let signer_seeds = &[&seeds[..]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.reward_token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
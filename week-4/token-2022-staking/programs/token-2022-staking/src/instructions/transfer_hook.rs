@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::onchain::invoke_transfer_checked, token_interface::TransferChecked,
+};
+
+// transfer_checked forwarding the accounts of a Token-2022 transfer hook from ctx.remaining_accounts
+// they are the hook program, its extra-account-metas account and every extra account it lists
+// they are found by address, so a mint without transfer hook just ignores them
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
@@ -1,27 +1,42 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface,transfer_checked, TransferChecked, close_account, CloseAccount}};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
 
-use crate::{error::MyErrorCode, Pool, StakeInfo, POOL_SEED, STAKEINFO_SEED};
+use crate::{
+    close_position, error::MyErrorCode, pay_reward, split_extra_reward_accounts,
+    transfer_checked_with_hook, transfer_extra_rewards, Pool, ReceiptMode, RewardAccounts,
+    StakeInfo, Vesting, POOL_SEED, STAKEINFO_SEED, VESTING_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Unstake<'info> {
     // signer is the staker
     #[account(mut)]
     pub signer: Signer<'info>,
     #[account(
-      seeds = [POOL_SEED, stake_mint.key().as_ref()],
+      mut,
+      seeds = [POOL_SEED, pool.config.as_ref(), stake_mint.key().as_ref()],
       bump,
-      has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,  
+      has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+      // with a cooldown, tokens leave the pool through request_unstake and withdraw_unbonded
+      constraint = pool.unbonding_period == 0 @MyErrorCode::UnbondingRequired,
     )]
     pub pool: Account<'info, Pool>,
+    // mut to harvest the transfer fees withheld in the stake_info_ata before closing it
     #[account(
+      mut,
       mint::token_program = stake_token_program,
     )]
     pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
       mut,
-      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()], 
-      bump
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()],
+      bump,
+      constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
     )]
     pub stake_info: Account<'info, StakeInfo>,
     #[account(
@@ -48,7 +63,7 @@ pub struct Unstake<'info> {
       mut,
       associated_token::mint = reward_mint,
       associated_token::authority = pool,
-      associated_token::token_program = reward_token_program,   
+      associated_token::token_program = reward_token_program,
     )]
     pub reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     // associated token account of the staker to receive reward token
@@ -57,104 +72,188 @@ pub struct Unstake<'info> {
       payer = signer,
       associated_token::mint = reward_mint,
       associated_token::authority = signer,
-      associated_token::token_program = reward_token_program,   
+      associated_token::token_program = reward_token_program,
     )]
     pub staker_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // vesting accounts, only required when the pool vests the reward
+    #[account(
+      init_if_needed,
+      payer = signer,
+      space = 8 + Vesting::INIT_SPACE,
+      seeds = [VESTING_SEED, pool.key().as_ref(), signer.key().as_ref()],
+      bump
+    )]
+    pub vesting: Option<Box<Account<'info, Vesting>>>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      associated_token::mint = reward_mint,
+      associated_token::authority = vesting,
+      associated_token::token_program = reward_token_program,
+    )]
+    pub vesting_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // receipt accounts, only required when the pool mints receipts
+    #[account(
+      mut,
+      address = pool.receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+      mut,
+      associated_token::mint = receipt_mint,
+      associated_token::authority = signer,
+      associated_token::token_program = receipt_token_program,
+    )]
+    pub staker_receipt_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
     pub stake_token_program: Interface<'info, TokenInterface>,
     pub reward_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-
 impl<'info> Unstake<'info> {
-  pub fn handler(&mut self, amount: u64, bumps: &UnstakeBumps) -> Result<()> {
-    require_gt!(amount, 0, MyErrorCode::InvalidUnstakeAmount);
-    require_gte!(self.stake_info.amount, amount, MyErrorCode::InsufficientStakeAmount );
-    // return the staked amount to the staker
-    self.back_to_staker(amount, bumps)?;
-    // return the reward to the staker
-    self.reward_to_staker(bumps)?;
-    // update the stake info
-    self.update_stake_info(amount,bumps)?;
-    Ok(())
-  }
-  
-
-  fn reward_to_staker(&mut self, bumps: &UnstakeBumps) -> Result<()> {
-    let seeds = &[POOL_SEED, self.stake_mint.to_account_info().key.as_ref(), &[bumps.pool]];
-    let signer_seeds = &[&seeds[..]];
-    let reward_amount = self.stake_info.reward.checked_add(self.pool.calculate_reward(self.stake_info.last_deposit_slot , Clock::get()?.slot)).ok_or(MyErrorCode::Overflow)?; 
-
-    transfer_checked(
-      CpiContext::new_with_signer(
-        self.reward_token_program.to_account_info(), 
-        TransferChecked {
-          from: self.reward_ata.to_account_info(),
-          to: self.staker_reward_ata.to_account_info(),
-          authority: self.pool.to_account_info(),
-          mint: self.reward_mint.to_account_info(),
-        }, 
-        signer_seeds
-      ), 
-      reward_amount, 
-      self.reward_mint.decimals
-    )?;
-
-    Ok(())
-  }
+    // remaining_accounts are the accounts of the extra rewards of the pool
+    // followed by the transfer hook accounts of the stake mint, if any
+    // returns the reward of reward_mint paid
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &UnstakeBumps,
+    ) -> Result<u64> {
+        let (extra_reward_accounts, hook_accounts) =
+            split_extra_reward_accounts(&self.pool, remaining_accounts)?;
+        require_gt!(amount, 0, MyErrorCode::InvalidUnstakeAmount);
+        require_gte!(
+            self.stake_info.amount,
+            amount,
+            MyErrorCode::InsufficientStakeAmount
+        );
+        let clock = Clock::get()?;
+        self.stake_info.check_vote_lock(clock.unix_timestamp)?;
+        // the receipts of the unstaked amount are burned first
+        self.burn_receipt(amount)?;
+        // settle the reward and remove the amount from the stake info
+        let reward_amount = self.stake_info.withdraw(&mut self.pool, amount, &clock)?;
+        let extra_amounts = self.stake_info.take_extra_rewards(&mut self.pool)?;
+        // return the staked amount to the staker
+        self.back_to_staker(amount, hook_accounts, bumps)?;
+        let seeds = &[
+            POOL_SEED,
+            self.pool.config.as_ref(),
+            self.stake_mint.to_account_info().key.as_ref(),
+            &[bumps.pool],
+        ];
+        // return the reward to the staker, or to its vesting account
+        pay_reward(
+            RewardAccounts {
+                pool: &self.pool,
+                staker: self.signer.key(),
+                reward_mint: &self.reward_mint,
+                reward_ata: &self.reward_ata,
+                staker_reward_ata: &self.staker_reward_ata,
+                vesting: self.vesting.as_deref_mut(),
+                vesting_ata: self.vesting_ata.as_deref_mut(),
+                reward_token_program: &self.reward_token_program,
+            },
+            reward_amount,
+            &[&seeds[..]],
+        )?;
+        // return the extra rewards to the staker, their accounts are passed in remaining_accounts
+        transfer_extra_rewards(
+            &self.pool,
+            extra_reward_accounts,
+            &extra_amounts,
+            &self.signer.key(),
+            &[&seeds[..]],
+        )?;
+        // close the stake info once everything is unstaked
+        self.update_stake_info(bumps)?;
+        Ok(reward_amount)
+    }
 
-  fn update_stake_info(&mut self, amount: u64, bumps: &UnstakeBumps) -> Result<()> {
-    //calculate the new stake amount
-    let new_amount = self.stake_info.amount.checked_sub(amount).ok_or(MyErrorCode::Overflow)?;
-    self.stake_info.amount = new_amount;
-    self.stake_info.reward = 0;
-    self.stake_info.last_deposit_slot = Clock::get()?.slot;
+    fn burn_receipt(&mut self, amount: u64) -> Result<()> {
+        if self.pool.receipt_mode == ReceiptMode::None {
+            return Ok(());
+        }
+        let (Some(receipt_mint), Some(staker_receipt_ata), Some(receipt_token_program)) = (
+            &self.receipt_mint,
+            &self.staker_receipt_ata,
+            &self.receipt_token_program,
+        ) else {
+            return err!(MyErrorCode::MissingReceiptAccounts);
+        };
 
-    // check if staker unstake all tokens
-    // close the stake_info_ata account and stake_info account
-    // return rent fee to the staker
-    if new_amount.eq(&0) {
-      let seeds = &[STAKEINFO_SEED, self.pool.to_account_info().key.as_ref(), self.signer.to_account_info().key.as_ref(),&[bumps.stake_info]];
-      let signer_seeds = &[&seeds[..]];
+        // a liquid receipt that was transferred away must be bought back before unstaking
+        burn(
+            CpiContext::new(
+                receipt_token_program.to_account_info(),
+                Burn {
+                    mint: receipt_mint.to_account_info(),
+                    from: staker_receipt_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
 
-      // call cpi close spl-token account
-      close_account(
-        CpiContext::new_with_signer(self.stake_token_program.to_account_info(), 
-        CloseAccount { 
-            account: self.stake_info_ata.to_account_info(), 
-            destination: self.signer.to_account_info(), 
-            authority: self.stake_info.to_account_info() 
-          },
-          signer_seeds
-        )
-      )?;
+    fn update_stake_info(&mut self, bumps: &UnstakeBumps) -> Result<()> {
+        // check if staker unstake all tokens and has no unbonding ticket left
+        // close the stake_info_ata account and stake_info account
+        // return rent fee to the staker
+        if self.stake_info.is_empty() {
+            let seeds = &[
+                STAKEINFO_SEED,
+                self.pool.to_account_info().key.as_ref(),
+                self.signer.to_account_info().key.as_ref(),
+                &[bumps.stake_info],
+            ];
+            close_position(
+                &mut self.stake_info,
+                &self.stake_info_ata,
+                &self.stake_mint,
+                &self.stake_token_program,
+                self.signer.to_account_info(),
+                &[&seeds[..]],
+            )?;
+        }
 
-      self.stake_info.close(self.signer.to_account_info())?;
+        Ok(())
     }
 
-    Ok(())
-  }
+    fn back_to_staker(
+        &mut self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        bumps: &UnstakeBumps,
+    ) -> Result<()> {
+        let seeds = &[
+            STAKEINFO_SEED,
+            self.pool.to_account_info().key.as_ref(),
+            self.signer.to_account_info().key.as_ref(),
+            &[bumps.stake_info],
+        ];
+        let signer_seeds = &[&seeds[..]];
 
-  fn back_to_staker(&mut self, amount: u64,bumps: &UnstakeBumps) -> Result<()> {
-    let seeds = &[STAKEINFO_SEED, self.pool.to_account_info().key.as_ref(), self.signer.to_account_info().key.as_ref(),&[bumps.stake_info]];
-    let signer_seeds = &[&seeds[..]];
-
-    // transfer the staked amount to the staker
-    transfer_checked(
-      CpiContext::new_with_signer(
-self.stake_token_program.to_account_info(), 
-TransferChecked {
-          from: self.stake_info_ata.to_account_info(),
-          to: self.staker_ata.to_account_info(),
-          authority: self.stake_info.to_account_info(),
-          mint: self.stake_mint.to_account_info(),
-        }, 
-       signer_seeds
-      ), 
-      amount, 
-      self.stake_mint.decimals
-    )?;
-    Ok(())
-  }
-}
\ No newline at end of file
+        // transfer the staked amount to the staker
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.stake_info_ata.to_account_info(),
+                    to: self.staker_ata.to_account_info(),
+                    authority: self.stake_info.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.stake_mint.decimals,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    token_2022::{
+        spl_token_2022::{
+            extension::{BaseStateWithExtensions, StateWithExtensions},
+            state::Mint,
+        },
+        Token2022,
+    },
+    token_2022_extensions::{self},
+    token_interface::{
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        TokenMetadataUpdateField,
+    },
+};
+
+use crate::{Config, CONFIG_SEED};
+
+#[derive(Accounts)]
+pub struct UpdateRewardMetadata<'info> {
+    // only the authority of the config can change the metadata, it also pays the extra rent
+    #[account(
+        mut,
+        address = config.authority
+    )]
+    pub signer: Signer<'info>,
+    // the config account is the update authority of the metadata
+    #[account(
+        seeds = [CONFIG_SEED, config.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the reward mint of the config, the metadata is stored in the mint itself
+    #[account(
+        mut,
+        address = config.reward_mint
+    )]
+    pub reward_mint: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateRewardMetadata<'info> {
+    pub fn handler(
+        &mut self,
+        field: String,
+        value: String,
+        bumps: UpdateRewardMetadataBumps,
+    ) -> Result<()> {
+        // name, symbol and uri are the standard fields, other keys go to additional_metadata
+        let field = match field.as_str() {
+            "name" => Field::Name,
+            "symbol" => Field::Symbol,
+            "uri" => Field::Uri,
+            _ => Field::Key(field),
+        };
+
+        // token 2022 reallocates the mint but does not fund it, so top up the rent first
+        let new_size = {
+            let mint_info = self.reward_mint.to_account_info();
+            let data = mint_info.try_borrow_data()?;
+            let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+            let mut metadata = mint.get_variable_len_extension::<TokenMetadata>()?;
+            let old_tlv_size = metadata.tlv_size_of()?;
+            metadata.update(field.clone(), value.clone());
+            data.len() + metadata.tlv_size_of()? - old_tlv_size
+        };
+        let required_lamports = Rent::get()?.minimum_balance(new_size);
+        let current_lamports = self.reward_mint.lamports();
+        if required_lamports > current_lamports {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.signer.to_account_info(),
+                        to: self.reward_mint.to_account_info(),
+                    },
+                ),
+                required_lamports - current_lamports,
+            )?;
+        }
+
+        let id = self.config.id.to_le_bytes();
+        let seeds = &[CONFIG_SEED, id.as_ref(), &[bumps.config]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_2022_extensions::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    token_program_id: self.token_program.to_account_info(),
+                    metadata: self.reward_mint.to_account_info(),
+                    update_authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            field,
+            value,
+        )?;
+
+        Ok(())
+    }
+}
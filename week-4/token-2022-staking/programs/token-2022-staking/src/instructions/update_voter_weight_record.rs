@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::MyErrorCode, Pool, StakeInfo, VoterWeightRecord, STAKEINFO_SEED, VOTER_WEIGHT_SEED,
+};
+
+#[derive(Accounts)]
+pub struct UpdateVoterWeightRecord<'info> {
+    // signer is the staker, it pays the record
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+      constraint = pool.governance_realm != Pubkey::default() @MyErrorCode::GovernanceNotConfigured,
+    )]
+    pub pool: Account<'info, Pool>,
+    // mut to lock the stake while the weight can be used
+    #[account(
+      mut,
+      seeds = [STAKEINFO_SEED, pool.key().as_ref(), signer.key().as_ref()],
+      bump,
+      constraint = stake_info.pool_nonce == pool.nonce @MyErrorCode::StaleStakeInfo,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+    #[account(
+      init_if_needed,
+      payer = signer,
+      space = 8 + VoterWeightRecord::INIT_SPACE,
+      seeds = [VOTER_WEIGHT_SEED, pool.key().as_ref(), signer.key().as_ref()],
+      bump
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateVoterWeightRecord<'info> {
+    // the weight expires at the end of the slot, so it must be refreshed in the transaction that votes
+    // the stake can't be unstaked and staked again from another wallet to vote twice
+    pub fn handler(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+        self.stake_info
+            .lock_for_vote(&self.pool, clock.unix_timestamp)?;
+        self.voter_weight_record.set_inner(VoterWeightRecord {
+            realm: self.pool.governance_realm,
+            governing_token_mint: self.pool.governing_token_mint,
+            governing_token_owner: self.signer.key(),
+            voter_weight: self.stake_info.voter_weight(&self.pool)?,
+            voter_weight_expiry: Some(clock.slot),
+            weight_action: None,
+            weight_action_target: None,
+            reserved: [0; 8],
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    close_position, error::MyErrorCode, harvest_withheld_fees, transfer_checked_with_hook,
+    StakeInfo, UnbondingTicket, STAKEINFO_SEED, UNBONDING_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawUnbonded<'info> {
+    // signer is the staker, rent of the ticket goes back to it
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // the pool is not needed, a ticket can be withdrawn after the pool is closed
+    #[account(
+        mut,
+        seeds = [UNBONDING_SEED, ticket.pool.as_ref(), signer.key().as_ref(), ticket.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
+        close = signer,
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+    // closed with its stake_info_ata once nothing is staked, unbonding nor owed anymore
+    #[account(
+        mut,
+        seeds = [STAKEINFO_SEED, ticket.pool.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = stake_info,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub stake_info_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    // mut to harvest the transfer fees withheld in the ticket_ata before closing it
+    #[account(
+        mut,
+        mint::token_program = stake_token_program,
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = ticket,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub ticket_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawUnbonded<'info> {
+    // remaining_accounts are the transfer hook accounts of the stake mint, if any
+    pub fn handler(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &WithdrawUnbondedBumps,
+    ) -> Result<()> {
+        require_gte!(
+            Clock::get()?.unix_timestamp,
+            self.ticket.unlock_at,
+            MyErrorCode::UnbondingNotFinished
+        );
+
+        let id = self.ticket.id.to_le_bytes();
+        let seeds = &[
+            UNBONDING_SEED,
+            self.ticket.pool.as_ref(),
+            self.ticket.staker.as_ref(),
+            id.as_ref(),
+            &[bumps.ticket],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // return the unbonded amount to the staker
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.ticket_ata.to_account_info(),
+                    mint: self.stake_mint.to_account_info(),
+                    to: self.staker_ata.to_account_info(),
+                    authority: self.ticket.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            self.ticket.amount,
+            self.stake_mint.decimals,
+        )?;
+
+        // an account still holding withheld transfer fees can't be closed
+        harvest_withheld_fees(
+            &self.stake_token_program.to_account_info(),
+            &self.stake_mint.to_account_info(),
+            &self.ticket_ata.to_account_info(),
+        )?;
+
+        // close the ticket_ata and return rent fee to the staker, the ticket is closed by anchor
+        close_account(CpiContext::new_with_signer(
+            self.stake_token_program.to_account_info(),
+            CloseAccount {
+                account: self.ticket_ata.to_account_info(),
+                destination: self.signer.to_account_info(),
+                authority: self.ticket.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        self.stake_info.unbonding_count = self
+            .stake_info
+            .unbonding_count
+            .checked_sub(1)
+            .ok_or(MyErrorCode::Overflow)?;
+        if self.stake_info.is_empty() {
+            self.close_stake_info(bumps)?;
+        }
+        Ok(())
+    }
+
+    // the last ticket of a position without stake nor reward left, return the rent to the staker
+    fn close_stake_info(&mut self, bumps: &WithdrawUnbondedBumps) -> Result<()> {
+        let seeds = &[
+            STAKEINFO_SEED,
+            self.ticket.pool.as_ref(),
+            self.ticket.staker.as_ref(),
+            &[bumps.stake_info],
+        ];
+        close_position(
+            &mut self.stake_info,
+            &self.stake_info_ata,
+            &self.stake_mint,
+            &self.stake_token_program,
+            self.signer.to_account_info(),
+            &[&seeds[..]],
+        )
+    }
+}
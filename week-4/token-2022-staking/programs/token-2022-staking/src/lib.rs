@@ -1,11 +1,13 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -15,24 +17,307 @@ declare_id!("7Fnpgk8zMDPBvGaRF1QsedeB8yYCjZudKqx4kXNQm1Nd");
 pub mod token_2022_staking {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        ctx.accounts.handler(ctx.bumps)
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        id: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        decimals: u8,
+        additional_metadata: Vec<MetadataField>,
+    ) -> Result<()> {
+        ctx.accounts.handler(
+            id,
+            name,
+            symbol,
+            uri,
+            decimals,
+            additional_metadata,
+            &ctx.bumps,
+        )?;
+        emit_cpi!(ConfigInitialized {
+            config: ctx.accounts.config.key(),
+            id,
+            authority: ctx.accounts.config.authority,
+            reward_mint: ctx.accounts.config.reward_mint,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
     }
 
     pub fn create_pool(
         ctx: Context<CreatePool>,
         allocation: u64,
-        reward_per_second: u64,
+        reward_rate: u64,
+        emission_mode: EmissionMode,
+        emission_end: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(
+            allocation,
+            reward_rate,
+            emission_mode,
+            emission_end,
+            &ctx.bumps,
+        )?;
+        let pool = &ctx.accounts.pool;
+        emit_cpi!(PoolCreated {
+            config: pool.config,
+            pool: pool.key(),
+            stake_mint: pool.stake_mint,
+            reward_mint: pool.reward_mint,
+            allocation: pool.allocation,
+            reward_rate,
+            emission_mode,
+            emission_end,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn stake<'info>(ctx: Context<'_, '_, '_, 'info, Stake<'info>>, amount: u64) -> Result<()> {
+        let amount = ctx
+            .accounts
+            .handler(amount, ctx.remaining_accounts, &ctx.bumps)?;
+        emit_cpi!(Staked {
+            pool: ctx.accounts.pool.key(),
+            staker: ctx.accounts.signer.key(),
+            amount,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn stake_for<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeFor<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let amount = ctx
+            .accounts
+            .handler(amount, ctx.remaining_accounts, &ctx.bumps)?;
+        emit_cpi!(Staked {
+            pool: ctx.accounts.pool.key(),
+            staker: ctx.accounts.beneficiary.key(),
+            amount,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, Unstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let reward = ctx
+            .accounts
+            .handler(amount, ctx.remaining_accounts, &ctx.bumps)?;
+        let (pool, staker, slot) = (
+            ctx.accounts.pool.key(),
+            ctx.accounts.signer.key(),
+            Clock::get()?.slot,
+        );
+        emit_cpi!(Unstaked {
+            pool,
+            staker,
+            amount,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot,
+        });
+        if reward > 0 {
+            emit_cpi!(RewardPaid {
+                pool,
+                staker,
+                amount: reward,
+                vested: ctx.accounts.pool.vesting_duration > 0,
+                slot,
+            });
+        }
+        // the stake_info was closed by the handler
+        if ctx.accounts.stake_info.is_empty() {
+            emit_cpi!(PositionClosed { pool, staker, slot });
+        }
+        Ok(())
+    }
+
+    pub fn claim<'info>(ctx: Context<'_, '_, '_, 'info, Claim<'info>>) -> Result<()> {
+        let reward = ctx.accounts.handler(ctx.remaining_accounts, &ctx.bumps)?;
+        // a claim can pay only extra rewards
+        if reward > 0 {
+            emit_cpi!(RewardPaid {
+                pool: ctx.accounts.pool.key(),
+                staker: ctx.accounts.staker.key(),
+                amount: reward,
+                vested: ctx.accounts.pool.vesting_duration > 0,
+                slot: Clock::get()?.slot,
+            });
+        }
+        // the stake_info was closed by the handler
+        if ctx.accounts.stake_info.is_empty() {
+            emit_cpi!(PositionClosed {
+                pool: ctx.accounts.pool.key(),
+                staker: ctx.accounts.staker.key(),
+                slot: Clock::get()?.slot,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set_claim_delegate(ctx: Context<SetClaimDelegate>, delegate: Pubkey) -> Result<()> {
+        ctx.accounts.handler(delegate)
+    }
+
+    pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+        ctx.accounts.handler(&ctx.bumps)
+    }
+
+    pub fn set_vesting_schedule(
+        ctx: Context<SetVestingSchedule>,
+        cliff: u64,
+        duration: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(cliff, duration)
+    }
+
+    pub fn set_governance(
+        ctx: Context<SetGovernance>,
+        realm: Pubkey,
+        governing_token_mint: Pubkey,
+        vote_lock_period: u64,
     ) -> Result<()> {
         ctx.accounts
-            .handler(allocation, reward_per_second, ctx.bumps)
+            .handler(realm, governing_token_mint, vote_lock_period)
+    }
+
+    pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    // views, called through simulation, their result is the return data
+    pub fn pending_reward(ctx: Context<GetPendingReward>) -> Result<PendingReward> {
+        ctx.accounts.handler()
+    }
+
+    pub fn pool_info(ctx: Context<GetPoolInfo>) -> Result<PoolInfo> {
+        ctx.accounts.handler()
+    }
+
+    pub fn set_reward_rate(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+        ctx.accounts.handler(reward_rate)
     }
 
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount)
+    pub fn top_up(ctx: Context<TopUp>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount, ctx.bumps)
     }
 
-    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount, &ctx.bumps)
+    pub fn set_pool_paused(ctx: Context<SetPoolPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.handler(paused)
+    }
+
+    pub fn close_pool<'info>(ctx: Context<'_, '_, '_, 'info, ClosePool<'info>>) -> Result<()> {
+        ctx.accounts.handler(ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn set_unbonding_period(
+        ctx: Context<SetUnbondingPeriod>,
+        unbonding_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(unbonding_period)
+    }
+
+    pub fn request_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestUnstake<'info>>,
+        id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        let amount = ctx
+            .accounts
+            .handler(id, amount, ctx.remaining_accounts, &ctx.bumps)?;
+        emit_cpi!(Unstaked {
+            pool: ctx.accounts.pool.key(),
+            staker: ctx.accounts.signer.key(),
+            amount,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn withdraw_unbonded<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnbonded<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.handler(ctx.remaining_accounts, &ctx.bumps)?;
+        // the stake_info was closed by the handler with the last ticket
+        if ctx.accounts.stake_info.is_empty() {
+            emit_cpi!(PositionClosed {
+                pool: ctx.accounts.ticket.pool,
+                staker: ctx.accounts.signer.key(),
+                slot: Clock::get()?.slot,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn create_receipt_mint(
+        ctx: Context<CreateReceiptMint>,
+        receipt_mode: ReceiptMode,
+    ) -> Result<()> {
+        ctx.accounts.handler(receipt_mode, ctx.bumps)
+    }
+
+    pub fn enable_liquid_staking(ctx: Context<EnableLiquidStaking>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn liquid_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, LiquidStake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let (amount, shares) = ctx
+            .accounts
+            .handler(amount, ctx.remaining_accounts, &ctx.bumps)?;
+        emit_cpi!(LiquidStaked {
+            pool: ctx.accounts.pool.key(),
+            staker: ctx.accounts.signer.key(),
+            amount,
+            shares,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn liquid_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, LiquidUnstake<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        let amount = ctx
+            .accounts
+            .handler(shares, ctx.remaining_accounts, &ctx.bumps)?;
+        emit_cpi!(LiquidUnstaked {
+            pool: ctx.accounts.pool.key(),
+            staker: ctx.accounts.signer.key(),
+            amount,
+            shares,
+            total_staked: ctx.accounts.pool.total_staked,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn add_extra_reward(
+        ctx: Context<AddExtraReward>,
+        allocation: u64,
+        reward_rate: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(allocation, reward_rate)
+    }
+
+    pub fn update_reward_metadata(
+        ctx: Context<UpdateRewardMetadata>,
+        field: String,
+        value: String,
+    ) -> Result<()> {
+        ctx.accounts.handler(field, value, ctx.bumps)
     }
 }
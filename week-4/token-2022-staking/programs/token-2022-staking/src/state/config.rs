@@ -3,6 +3,10 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
+    // a deployment can run several configs, each one is derived from its id
+    pub id: u64,
     pub authority: Pubkey,
     pub reward_mint: Pubkey,
+    // pools created so far, the nonce of the next pool
+    pub pool_count: u64,
 }
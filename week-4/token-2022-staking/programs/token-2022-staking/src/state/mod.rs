@@ -4,3 +4,9 @@ pub mod pool;
 pub use pool::*;
 pub mod stake_info;
 pub use stake_info::*;
+pub mod unbonding_ticket;
+pub use unbonding_ticket::*;
+pub mod vesting;
+pub use vesting::*;
+pub mod voter_weight_record;
+pub use voter_weight_record::*;
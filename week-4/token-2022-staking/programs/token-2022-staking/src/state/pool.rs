@@ -1,19 +1,256 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::MyErrorCode, ACC_REWARD_PRECISION, MAX_EXTRA_REWARDS};
+
+// unit of the pool emission clock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum EmissionMode {
+    // reward_rate is paid per slot
+    Slot,
+    // reward_rate is paid per second of unix_timestamp
+    Timestamp,
+}
+
+// where the reward of the pool comes from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum RewardSource {
+    // reward is minted from the reward mint of the config
+    Minted,
+    // reward is an external mint deposited by the config authority
+    Deposited,
+}
+
+// receipt token minted to the staker for every staked token
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ReceiptMode {
+    // no receipt, the StakeInfo is the only record of a position
+    None,
+    // soulbound receipt, it stays in the wallet of the staker
+    NonTransferable,
+    // transferable receipt, it must be held back to unstake
+    Liquid,
+}
+
+// how stakers hold their stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum StakeMode {
+    // a StakeInfo per staker, rewards are claimed
+    Position,
+    // a share token per staked token, rewards compound into the value of the shares
+    // only for pools whose reward mint is the stake mint
+    Liquid,
+}
+
+// a reward token distributed by the pool on top of reward_mint, always deposited
+// it shares the emission clock of the pool but has its own rate and allocation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct ExtraReward {
+    pub mint: Pubkey,
+    pub reward_ata: Pubkey,
+    pub token_program: Pubkey,
+    pub allocation: u64,
+    pub reward_rate: u64,
+    pub distributed: u64,
+    // reward accumulated per staked token, scaled by ACC_REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    // reward settled to stake infos and not paid yet
+    pub owed: u64,
+}
+
+impl ExtraReward {
+    // accrue the emission of `elapsed` units of the pool emission clock
+    pub fn accrue(&mut self, elapsed: u64, total_staked: u64) -> Result<()> {
+        let reward = elapsed
+            .checked_mul(self.reward_rate)
+            .ok_or(MyErrorCode::Overflow)?
+            .min(self.remaining_allocation());
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(reward_per_share(reward, total_staked)?)
+            .ok_or(MyErrorCode::Overflow)?;
+        self.distributed = self
+            .distributed
+            .checked_add(reward)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn remaining_allocation(&self) -> u64 {
+        self.allocation.saturating_sub(self.distributed)
+    }
+
+    // reward earned by `amount` staked tokens since acc_reward_per_share was zero
+    pub fn accumulated_reward(&self, amount: u64) -> Result<u128> {
+        Ok(self
+            .acc_reward_per_share
+            .checked_mul(amount as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            / ACC_REWARD_PRECISION)
+    }
+}
+
+// share of `reward` for one staked token, scaled by ACC_REWARD_PRECISION
+fn reward_per_share(reward: u64, total_staked: u64) -> Result<u128> {
+    Ok((reward as u128)
+        .checked_mul(ACC_REWARD_PRECISION)
+        .ok_or(MyErrorCode::Overflow)?
+        / total_staked as u128)
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Pool {
+    // config the pool belongs to
+    pub config: Pubkey,
+    // pool_count of the config when the pool was created, tells apart pools re-created at the same address
+    pub nonce: u64,
     pub authority: Pubkey,
     pub stake_mint: Pubkey,
     pub reward_mint: Pubkey,
     pub reward_ata: Pubkey,
     pub allocation: u64,
-    pub reward_per_slot: u64,
+    // reward emitted per unit of the emission mode
+    pub reward_rate: u64,
+    // total amount staked by all stakers, used to split the emission
+    pub total_staked: u64,
+    // stake infos holding a stake, liquid share holders are not counted
+    pub staker_count: u64,
+    // reward accumulated per staked token, scaled by ACC_REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    // slot or unix timestamp depending on emission_mode
+    pub last_update: u64,
+    // reward accrued to stakers so far, never exceeds allocation
+    pub distributed: u64,
+    // reward settled to stake infos and not paid yet, the pool can't be closed while it is owed
+    pub reward_owed: u64,
+    // no reward accrues after this slot or unix timestamp
+    pub emission_end: u64,
+    // a paused pool accepts no stake and emits no reward
+    pub paused: bool,
+    pub emission_mode: EmissionMode,
+    pub reward_source: RewardSource,
+    #[max_len(MAX_EXTRA_REWARDS)]
+    pub extra_rewards: Vec<ExtraReward>,
+    pub receipt_mode: ReceiptMode,
+    // Token-2022 mint of the receipts, default while receipt_mode is None
+    pub receipt_mint: Pubkey,
+    pub stake_mode: StakeMode,
+    // Token-2022 mint of the shares, default while stake_mode is Position
+    pub share_mint: Pubkey,
+    // seconds an unbonding ticket waits before its tokens can be withdrawn, 0 unstakes immediately
+    pub unbonding_period: u64,
+    // claimed reward vests over vesting_duration seconds after a vesting_cliff, 0 pays it immediately
+    // only the reward of reward_mint vests, extra rewards are always paid immediately
+    pub vesting_cliff: u64,
+    pub vesting_duration: u64,
+    // spl-governance realm and governing mint the stakers vote with, default while disabled
+    pub governance_realm: Pubkey,
+    pub governing_token_mint: Pubkey,
+    // seconds the stake of a voter stays locked after its voter weight record is refreshed
+    // it must cover the voting time of the realm, so the same stake can't vote again from another wallet
+    pub vote_lock_period: u64,
 }
 
 impl Pool {
-    pub fn calculate_reward(&self, from_slot: u64, to_slot: u64) -> u64 {
-        let elapsed = to_slot - from_slot;
-        elapsed * self.reward_per_slot
+    // current position of the pool emission clock
+    pub fn now(&self, clock: &Clock) -> u64 {
+        match self.emission_mode {
+            EmissionMode::Slot => clock.slot,
+            EmissionMode::Timestamp => clock.unix_timestamp.max(0) as u64,
+        }
+    }
+
+    // total emission of the pool between two points of the emission clock
+    pub fn calculate_reward(&self, from: u64, to: u64) -> Result<u64> {
+        let elapsed = to.checked_sub(from).ok_or(MyErrorCode::Overflow)?;
+        Ok(elapsed
+            .checked_mul(self.reward_rate)
+            .ok_or(MyErrorCode::Overflow)?)
+    }
+
+    // accrue the emission since last_update into acc_reward_per_share
+    // in liquid mode it is added to total_staked instead, so every share is worth more
+    pub fn update(&mut self, clock: &Clock) -> Result<()> {
+        // emission stops at emission_end
+        let to = self.now(clock).min(self.emission_end);
+        if to <= self.last_update {
+            return Ok(());
+        }
+
+        // nobody is staking or the pool is paused, the emission of this period is not distributed
+        if self.total_staked == 0 || self.paused {
+            self.last_update = to;
+            return Ok(());
+        }
+
+        // the extra rewards are shared by the stake of the period, before the reward compounds into it
+        let staked = self.total_staked;
+        // emission stops once the whole allocation has been distributed
+        let reward = self
+            .calculate_reward(self.last_update, to)?
+            .min(self.remaining_allocation());
+
+        match self.stake_mode {
+            StakeMode::Position => {
+                self.acc_reward_per_share = self
+                    .acc_reward_per_share
+                    .checked_add(reward_per_share(reward, self.total_staked)?)
+                    .ok_or(MyErrorCode::Overflow)?;
+            }
+            StakeMode::Liquid => {
+                self.total_staked = self
+                    .total_staked
+                    .checked_add(reward)
+                    .ok_or(MyErrorCode::Overflow)?;
+            }
+        }
+        self.distributed = self
+            .distributed
+            .checked_add(reward)
+            .ok_or(MyErrorCode::Overflow)?;
+
+        // every extra reward accrues over the same period
+        let elapsed = to - self.last_update;
+        for extra_reward in self.extra_rewards.iter_mut() {
+            extra_reward.accrue(elapsed, staked)?;
+        }
+        self.last_update = to;
+        Ok(())
+    }
+
+    pub fn remaining_allocation(&self) -> u64 {
+        self.allocation.saturating_sub(self.distributed)
+    }
+
+    // shares minted for `amount` staked tokens, rounded down, pool must be updated first
+    pub fn shares_for(&self, amount: u64, share_supply: u64) -> Result<u64> {
+        if share_supply == 0 {
+            return Ok(amount);
+        }
+        let shares = (amount as u128)
+            .checked_mul(share_supply as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            .checked_div(self.total_staked as u128)
+            .ok_or(MyErrorCode::Overflow)?;
+        u64::try_from(shares).map_err(|_| error!(MyErrorCode::Overflow))
+    }
+
+    // staked tokens returned for `shares`, rounded down, pool must be updated first
+    pub fn underlying_for(&self, shares: u64, share_supply: u64) -> Result<u64> {
+        let amount = (shares as u128)
+            .checked_mul(self.total_staked as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            .checked_div(share_supply as u128)
+            .ok_or(MyErrorCode::Overflow)?;
+        u64::try_from(amount).map_err(|_| error!(MyErrorCode::Overflow))
+    }
+
+    // reward earned by `amount` staked tokens since acc_reward_per_share was zero
+    pub fn accumulated_reward(&self, amount: u64) -> Result<u128> {
+        Ok(self
+            .acc_reward_per_share
+            .checked_mul(amount as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            / ACC_REWARD_PRECISION)
     }
 }
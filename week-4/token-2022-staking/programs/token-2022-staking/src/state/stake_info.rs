@@ -1,10 +1,671 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    error::MyErrorCode, Pool, ACC_REWARD_PRECISION, MAX_EXTRA_REWARDS, MAX_LOCKUP_BONUS_PERIOD,
+};
+
+// reward of the staker for the extra reward of the pool at the same index
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct ExtraStakeReward {
+    pub reward: u64,
+    pub reward_debt: u128,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct StakeInfo {
     pub staker: Pubkey,
+    // nonce of the pool the stake info belongs to, see Pool::nonce
+    pub pool_nonce: u64,
     pub amount: u64,
     pub last_deposit_slot: u64,
     pub reward: u64,
+    // part of the pool accumulated reward that was already accounted to this stake
+    pub reward_debt: u128,
+    #[max_len(MAX_EXTRA_REWARDS)]
+    pub extra_rewards: Vec<ExtraStakeReward>,
+    // can claim the reward to the staker, default while there is none
+    pub claim_delegate: Pubkey,
+    // unbonding tickets not withdrawn yet, the stake info is kept open until they are
+    // they hold no reward, so a stake info bound to a new pool keeps counting them
+    pub unbonding_count: u64,
+    // unix timestamp until which the stake can't leave the pool, set when the voter weight is refreshed
+    pub vote_locked_until: i64,
+}
+
+impl StakeInfo {
+    // reward earned since the last update of reward_debt, pool must be updated first
+    pub fn pending_reward(&self, pool: &Pool) -> Result<u64> {
+        let pending = pool
+            .accumulated_reward(self.amount)?
+            .saturating_sub(self.reward_debt);
+        u64::try_from(pending).map_err(|_| error!(MyErrorCode::Overflow))
+    }
+
+    // rounded up so the pending reward never exceeds the staker share of the emission
+    pub fn update_reward_debt(&mut self, pool: &Pool) -> Result<()> {
+        self.reward_debt = pool
+            .acc_reward_per_share
+            .checked_mul(self.amount as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            .div_ceil(ACC_REWARD_PRECISION);
+        for (extra, pool_extra) in self.extra_rewards.iter_mut().zip(pool.extra_rewards.iter()) {
+            extra.reward_debt = pool_extra
+                .acc_reward_per_share
+                .checked_mul(self.amount as u128)
+                .ok_or(MyErrorCode::Overflow)?
+                .div_ceil(ACC_REWARD_PRECISION);
+        }
+        Ok(())
+    }
+
+    // move the reward earned since the last update into `reward`, pool must be updated first
+    // the pool keeps track of the settled reward it owes until it is paid
+    pub fn settle_reward(&mut self, pool: &mut Pool) -> Result<()> {
+        let pending = self.pending_reward(pool)?;
+        self.reward = self
+            .reward
+            .checked_add(pending)
+            .ok_or(MyErrorCode::Overflow)?;
+        pool.reward_owed = pool
+            .reward_owed
+            .checked_add(pending)
+            .ok_or(MyErrorCode::Overflow)?;
+
+        // an extra reward added after the last settlement starts with no debt
+        // its acc_reward_per_share only grew while the amount was unchanged
+        self.extra_rewards
+            .resize(pool.extra_rewards.len(), ExtraStakeReward::default());
+        for (extra, pool_extra) in self
+            .extra_rewards
+            .iter_mut()
+            .zip(pool.extra_rewards.iter_mut())
+        {
+            let pending = pool_extra
+                .accumulated_reward(self.amount)?
+                .saturating_sub(extra.reward_debt);
+            let pending = u64::try_from(pending).map_err(|_| error!(MyErrorCode::Overflow))?;
+            extra.reward = extra
+                .reward
+                .checked_add(pending)
+                .ok_or(MyErrorCode::Overflow)?;
+            pool_extra.owed = pool_extra
+                .owed
+                .checked_add(pending)
+                .ok_or(MyErrorCode::Overflow)?;
+        }
+        Ok(())
+    }
+
+    // nothing is staked, unbonding nor owed anymore, the stake info can be closed
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+            && self.unbonding_count == 0
+            && self.reward == 0
+            && self.extra_rewards.iter().all(|extra| extra.reward == 0)
+    }
+
+    // governance weight of the stake, the unbonding period of the pool is the lockup
+    // a longer lockup adds up to the staked amount again at MAX_LOCKUP_BONUS_PERIOD
+    pub fn voter_weight(&self, pool: &Pool) -> Result<u64> {
+        let lockup = pool.unbonding_period.min(MAX_LOCKUP_BONUS_PERIOD);
+        let bonus = (self.amount as u128)
+            .checked_mul(lockup as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            / MAX_LOCKUP_BONUS_PERIOD as u128;
+        let weight = (self.amount as u128)
+            .checked_add(bonus)
+            .ok_or(MyErrorCode::Overflow)?;
+        u64::try_from(weight).map_err(|_| error!(MyErrorCode::Overflow))
+    }
+
+    // the stake counted by a voter weight record stays in the pool until the vote is over
+    pub fn lock_for_vote(&mut self, pool: &Pool, now: i64) -> Result<()> {
+        let locked_until = i64::try_from(pool.vote_lock_period)
+            .ok()
+            .and_then(|period| now.checked_add(period))
+            .ok_or(MyErrorCode::Overflow)?;
+        self.vote_locked_until = self.vote_locked_until.max(locked_until);
+        Ok(())
+    }
+
+    pub fn check_vote_lock(&self, now: i64) -> Result<()> {
+        require_gte!(
+            now,
+            self.vote_locked_until,
+            MyErrorCode::PositionLockedForVote
+        );
+        Ok(())
+    }
+
+    // return the settled reward of every extra reward of the pool and reset it
+    pub fn take_extra_rewards(&mut self, pool: &mut Pool) -> Result<Vec<u64>> {
+        self.extra_rewards
+            .iter_mut()
+            .zip(pool.extra_rewards.iter_mut())
+            .map(|(extra, pool_extra)| {
+                let reward = std::mem::take(&mut extra.reward);
+                pool_extra.owed = pool_extra
+                    .owed
+                    .checked_sub(reward)
+                    .ok_or(MyErrorCode::Overflow)?;
+                Ok(reward)
+            })
+            .collect()
+    }
+
+    // a stake info left by a closed pool at the same address starts over in the new pool
+    // its stake and reward were all withdrawn before the old pool could be closed
+    fn bind(&mut self, pool: &Pool) {
+        if self.pool_nonce != pool.nonce {
+            self.reward = 0;
+            self.reward_debt = 0;
+            self.extra_rewards.clear();
+            self.claim_delegate = Pubkey::default();
+            self.pool_nonce = pool.nonce;
+        }
+    }
+
+    pub fn deposit(&mut self, pool: &mut Pool, amount: u64, clock: &Clock) -> Result<()> {
+        self.bind(pool);
+        // accrue the pool reward up to now before the total staked changes
+        pool.update(clock)?;
+        // keep the reward earned by the previous amount
+        self.settle_reward(pool)?;
+
+        if self.amount == 0 && amount > 0 {
+            pool.staker_count = pool
+                .staker_count
+                .checked_add(1)
+                .ok_or(MyErrorCode::Overflow)?;
+        }
+        self.amount = self
+            .amount
+            .checked_add(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        // new stake only earns reward accumulated from now on
+        self.update_reward_debt(pool)?;
+        self.last_deposit_slot = clock.slot;
+
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // return the whole reward owed to the staker, the staked amount is unchanged
+    pub fn claim(&mut self, pool: &mut Pool, clock: &Clock) -> Result<u64> {
+        self.withdraw(pool, 0, clock)
+    }
+
+    // a staker is no longer counted once its whole stake is removed
+    fn remove_amount(&mut self, pool: &mut Pool, amount: u64) -> Result<()> {
+        let had_stake = self.amount > 0;
+        self.amount = self
+            .amount
+            .checked_sub(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        if had_stake && self.amount == 0 {
+            pool.staker_count = pool
+                .staker_count
+                .checked_sub(1)
+                .ok_or(MyErrorCode::Overflow)?;
+        }
+        Ok(())
+    }
+
+    // remove `amount` from the stake, the reward owed so far stays settled in the stake info
+    pub fn unbond(&mut self, pool: &mut Pool, amount: u64, clock: &Clock) -> Result<()> {
+        // accrue the pool reward up to now before the total staked changes
+        pool.update(clock)?;
+        self.settle_reward(pool)?;
+
+        self.remove_amount(pool, amount)?;
+        // the unbonding amount earns nothing from now on
+        self.update_reward_debt(pool)?;
+
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // remove `amount` from the stake and return the whole reward owed to the staker
+    pub fn withdraw(&mut self, pool: &mut Pool, amount: u64, clock: &Clock) -> Result<u64> {
+        // accrue the pool reward up to now before the total staked changes
+        pool.update(clock)?;
+        self.settle_reward(pool)?;
+        let reward = self.reward;
+        pool.reward_owed = pool
+            .reward_owed
+            .checked_sub(reward)
+            .ok_or(MyErrorCode::Overflow)?;
+
+        self.remove_amount(pool, amount)?;
+        self.reward = 0;
+        self.update_reward_debt(pool)?;
+        self.last_deposit_slot = clock.slot;
+
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(MyErrorCode::Overflow)?;
+        Ok(reward)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmissionMode, ExtraReward, ReceiptMode, RewardSource, StakeMode};
+    use proptest::prelude::*;
+
+    const STAKERS: usize = 3;
+
+    fn new_pool(reward_rate: u64, allocation: u64, emission_end: u64) -> Pool {
+        Pool {
+            config: Pubkey::default(),
+            nonce: 0,
+            authority: Pubkey::default(),
+            stake_mint: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            reward_ata: Pubkey::default(),
+            allocation,
+            reward_rate,
+            total_staked: 0,
+            staker_count: 0,
+            acc_reward_per_share: 0,
+            last_update: 0,
+            distributed: 0,
+            reward_owed: 0,
+            emission_end,
+            paused: false,
+            emission_mode: EmissionMode::Slot,
+            reward_source: RewardSource::Minted,
+            extra_rewards: vec![],
+            receipt_mode: ReceiptMode::None,
+            receipt_mint: Pubkey::default(),
+            stake_mode: StakeMode::Position,
+            share_mint: Pubkey::default(),
+            unbonding_period: 0,
+            vesting_cliff: 0,
+            vesting_duration: 0,
+            governance_realm: Pubkey::default(),
+            governing_token_mint: Pubkey::default(),
+            vote_lock_period: 0,
+        }
+    }
+
+    fn new_stake_info() -> StakeInfo {
+        StakeInfo {
+            staker: Pubkey::default(),
+            pool_nonce: 0,
+            amount: 0,
+            last_deposit_slot: 0,
+            reward: 0,
+            reward_debt: 0,
+            extra_rewards: vec![],
+            claim_delegate: Pubkey::default(),
+            unbonding_count: 0,
+            vote_locked_until: 0,
+        }
+    }
+
+    fn clock(slot: u64) -> Clock {
+        Clock {
+            slot,
+            ..Clock::default()
+        }
+    }
+
+    #[test]
+    fn restake_keeps_accrued_reward() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        stake_info.deposit(&mut pool, 1_000, &clock(10)).unwrap();
+        assert_eq!(stake_info.reward, 1_000);
+
+        let reward = stake_info.withdraw(&mut pool, 2_000, &clock(20)).unwrap();
+        assert_eq!(reward, 2_000);
+        assert_eq!(stake_info.reward, 0);
+        assert_eq!(pool.total_staked, 0);
+    }
+
+    #[test]
+    fn claim_keeps_stake_and_resets_reward() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        assert_eq!(stake_info.claim(&mut pool, &clock(10)).unwrap(), 1_000);
+        assert_eq!(stake_info.amount, 1_000);
+        assert_eq!(stake_info.last_deposit_slot, 10);
+        assert_eq!(stake_info.claim(&mut pool, &clock(10)).unwrap(), 0);
+        assert_eq!(stake_info.claim(&mut pool, &clock(15)).unwrap(), 500);
+    }
+
+    #[test]
+    fn pool_owes_settled_reward_until_it_is_paid() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        // the whole stake leaves, its reward stays settled in the stake info
+        stake_info.unbond(&mut pool, 1_000, &clock(10)).unwrap();
+        assert_eq!(pool.total_staked, 0);
+        assert_eq!(pool.reward_owed, 1_000);
+
+        assert_eq!(stake_info.claim(&mut pool, &clock(20)).unwrap(), 1_000);
+        assert_eq!(pool.reward_owed, 0);
+    }
+
+    #[test]
+    fn stake_info_of_a_closed_pool_starts_over() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        stake_info.claim_delegate = Pubkey::new_unique();
+        stake_info.withdraw(&mut pool, 1_000, &clock(10)).unwrap();
+
+        // the pool is re-created at the same address with a higher acc_reward_per_share
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        pool.nonce = 1;
+        pool.acc_reward_per_share = ACC_REWARD_PRECISION;
+        stake_info.deposit(&mut pool, 1_000, &clock(20)).unwrap();
+        assert_eq!(stake_info.pool_nonce, 1);
+        assert_eq!(stake_info.claim_delegate, Pubkey::default());
+        assert_eq!(stake_info.reward_debt, 1_000);
+        assert_eq!(stake_info.claim(&mut pool, &clock(30)).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn stake_info_is_empty_once_nothing_is_staked_unbonding_or_owed() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        stake_info.unbond(&mut pool, 1_000, &clock(10)).unwrap();
+        stake_info.unbonding_count = 1;
+        assert!(!stake_info.is_empty());
+
+        stake_info.unbonding_count = 0;
+        assert!(!stake_info.is_empty());
+        stake_info.claim(&mut pool, &clock(10)).unwrap();
+        assert!(stake_info.is_empty());
+    }
+
+    #[test]
+    fn reward_is_split_by_stake() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut alice = new_stake_info();
+        let mut bob = new_stake_info();
+
+        alice.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        bob.deposit(&mut pool, 3_000, &clock(0)).unwrap();
+
+        assert_eq!(alice.withdraw(&mut pool, 1_000, &clock(10)).unwrap(), 250);
+        assert_eq!(bob.withdraw(&mut pool, 3_000, &clock(10)).unwrap(), 750);
+    }
+
+    #[test]
+    fn unbonded_amount_stops_earning_but_keeps_reward() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut alice = new_stake_info();
+        let mut bob = new_stake_info();
+
+        alice.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        bob.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+
+        // alice unbonds half of her stake at slot 10, she keeps the 500 earned so far
+        alice.unbond(&mut pool, 500, &clock(10)).unwrap();
+        assert_eq!(alice.amount, 500);
+        assert_eq!(alice.reward, 500);
+        assert_eq!(pool.total_staked, 1_500);
+
+        // the next 1_500 is split 1:2
+        assert_eq!(alice.claim(&mut pool, &clock(25)).unwrap(), 1_000);
+        assert_eq!(bob.claim(&mut pool, &clock(25)).unwrap(), 1_500);
+    }
+
+    #[test]
+    fn staker_count_follows_positions_holding_a_stake() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut alice = new_stake_info();
+        let mut bob = new_stake_info();
+
+        alice.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        alice.deposit(&mut pool, 1_000, &clock(1)).unwrap();
+        bob.deposit(&mut pool, 1_000, &clock(2)).unwrap();
+        assert_eq!(pool.staker_count, 2);
+
+        alice.claim(&mut pool, &clock(3)).unwrap();
+        alice.withdraw(&mut pool, 1_000, &clock(4)).unwrap();
+        assert_eq!(pool.staker_count, 2);
+
+        alice.withdraw(&mut pool, 1_000, &clock(5)).unwrap();
+        bob.unbond(&mut pool, 1_000, &clock(6)).unwrap();
+        assert_eq!(pool.staker_count, 0);
+        assert_eq!(pool.total_staked, 0);
+    }
+
+    #[test]
+    fn voter_weight_grows_with_the_lockup() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+
+        assert_eq!(stake_info.voter_weight(&pool).unwrap(), 1_000);
+        pool.unbonding_period = MAX_LOCKUP_BONUS_PERIOD / 2;
+        assert_eq!(stake_info.voter_weight(&pool).unwrap(), 1_500);
+        pool.unbonding_period = MAX_LOCKUP_BONUS_PERIOD * 10;
+        assert_eq!(stake_info.voter_weight(&pool).unwrap(), 2_000);
+    }
+
+    #[test]
+    fn paused_pool_emits_nothing() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut stake_info = new_stake_info();
+
+        stake_info.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        pool.update(&clock(10)).unwrap();
+        pool.paused = true;
+        pool.update(&clock(20)).unwrap();
+        pool.paused = false;
+
+        assert_eq!(
+            stake_info.withdraw(&mut pool, 1_000, &clock(30)).unwrap(),
+            2_000
+        );
+    }
+
+    #[test]
+    fn timestamp_mode_emits_per_second() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        pool.emission_mode = EmissionMode::Timestamp;
+        let mut stake_info = new_stake_info();
+
+        let at = |slot: u64, unix_timestamp: i64| Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        };
+
+        stake_info.deposit(&mut pool, 1_000, &at(0, 1_000)).unwrap();
+        // slots advanced faster than time, only the elapsed seconds count
+        let reward = stake_info
+            .withdraw(&mut pool, 1_000, &at(50, 1_010))
+            .unwrap();
+        assert_eq!(reward, 1_000);
+    }
+
+    #[test]
+    fn extra_reward_accrues_from_when_it_is_added() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        let mut alice = new_stake_info();
+        let mut bob = new_stake_info();
+
+        alice.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+        bob.deposit(&mut pool, 1_000, &clock(0)).unwrap();
+
+        // an extra reward is added at slot 10 with a budget of 500
+        pool.update(&clock(10)).unwrap();
+        pool.extra_rewards.push(ExtraReward {
+            mint: Pubkey::default(),
+            reward_ata: Pubkey::default(),
+            token_program: Pubkey::default(),
+            allocation: 500,
+            reward_rate: 10,
+            distributed: 0,
+            acc_reward_per_share: 0,
+            owed: 0,
+        });
+
+        assert_eq!(alice.claim(&mut pool, &clock(20)).unwrap(), 1_000);
+        assert_eq!(alice.take_extra_rewards(&mut pool).unwrap(), vec![50]);
+        assert_eq!(alice.take_extra_rewards(&mut pool).unwrap(), vec![0]);
+
+        // bob settles the extra reward for the first time, the allocation caps the emission
+        assert_eq!(bob.withdraw(&mut pool, 1_000, &clock(100)).unwrap(), 5_000);
+        assert_eq!(bob.take_extra_rewards(&mut pool).unwrap(), vec![250]);
+        assert_eq!(alice.claim(&mut pool, &clock(100)).unwrap(), 4_000);
+        assert_eq!(alice.take_extra_rewards(&mut pool).unwrap(), vec![200]);
+        assert_eq!(pool.extra_rewards[0].distributed, 500);
+        assert_eq!(pool.extra_rewards[0].owed, 0);
+    }
+
+    #[test]
+    fn liquid_shares_appreciate_with_compounded_reward() {
+        let mut pool = new_pool(100, 1_500, u64::MAX);
+        pool.stake_mode = StakeMode::Liquid;
+        let mut share_supply = 0;
+
+        // alice stakes 1_000 and gets 1_000 shares
+        pool.update(&clock(0)).unwrap();
+        let alice_shares = pool.shares_for(1_000, share_supply).unwrap();
+        pool.total_staked += 1_000;
+        share_supply += alice_shares;
+        assert_eq!(alice_shares, 1_000);
+
+        // 1_000 reward compounds, bob's 1_000 only buys half as many shares
+        pool.update(&clock(10)).unwrap();
+        assert_eq!(pool.total_staked, 2_000);
+        let bob_shares = pool.shares_for(1_000, share_supply).unwrap();
+        pool.total_staked += 1_000;
+        share_supply += bob_shares;
+        assert_eq!(bob_shares, 500);
+
+        // the last 500 of the allocation is split 2:1
+        pool.update(&clock(100)).unwrap();
+        assert_eq!(pool.total_staked, 3_500);
+        assert_eq!(pool.distributed, 1_500);
+        assert_eq!(
+            pool.underlying_for(alice_shares, share_supply).unwrap(),
+            2_333
+        );
+        assert_eq!(
+            pool.underlying_for(bob_shares, share_supply).unwrap(),
+            1_166
+        );
+        assert_eq!(pool.acc_reward_per_share, 0);
+    }
+
+    #[test]
+    fn liquid_extra_reward_is_shared_by_the_stake_before_compounding() {
+        let mut pool = new_pool(100, u64::MAX, u64::MAX);
+        pool.stake_mode = StakeMode::Liquid;
+        pool.total_staked = 1_000;
+        pool.extra_rewards.push(ExtraReward {
+            mint: Pubkey::default(),
+            reward_ata: Pubkey::default(),
+            token_program: Pubkey::default(),
+            allocation: u64::MAX,
+            reward_rate: 10,
+            distributed: 0,
+            acc_reward_per_share: 0,
+            owed: 0,
+        });
+
+        // 1_000 compounds, the 100 of extra reward is still split over the 1_000 staked during the period
+        pool.update(&clock(10)).unwrap();
+        assert_eq!(pool.total_staked, 2_000);
+        assert_eq!(
+            pool.extra_rewards[0].accumulated_reward(1_000).unwrap(),
+            100
+        );
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Stake { staker: usize, amount: u64 },
+        Unstake { staker: usize, amount: u64 },
+    }
+
+    fn op() -> impl Strategy<Value = (Op, u64)> {
+        let op = prop_oneof![
+            (0..STAKERS, 1..1_000_000_000u64)
+                .prop_map(|(staker, amount)| Op::Stake { staker, amount }),
+            (0..STAKERS, 1..1_000_000_000u64)
+                .prop_map(|(staker, amount)| Op::Unstake { staker, amount }),
+        ];
+        (op, 0..100u64)
+    }
+
+    proptest! {
+        #[test]
+        fn rewards_are_never_lost_or_double_counted(
+            reward_rate in 1..1_000_000_000u64,
+            allocation in 1..1_000_000_000_000u64,
+            end_slot in 1..5_000u64,
+            ops in prop::collection::vec(op(), 1..50),
+        ) {
+            let mut pool = new_pool(reward_rate, allocation, end_slot);
+            let mut stake_infos = vec![new_stake_info(); STAKERS];
+            let mut slot = 0;
+            let mut paid = 0u64;
+            // emission expected from the stake history, computed independently of the pool
+            let mut expected = 0u64;
+            let mut accounted_slot = 0;
+
+            for (op, slot_delta) in ops {
+                slot += slot_delta;
+
+                let to_slot = slot.min(end_slot);
+                if pool.total_staked > 0 && to_slot > accounted_slot {
+                    let emission = (to_slot - accounted_slot) * reward_rate;
+                    expected += emission.min(allocation - expected);
+                }
+                accounted_slot = accounted_slot.max(to_slot);
+
+                match op {
+                    Op::Stake { staker, amount } => {
+                        stake_infos[staker].deposit(&mut pool, amount, &clock(slot)).unwrap();
+                    }
+                    Op::Unstake { staker, amount } => {
+                        let amount = amount.min(stake_infos[staker].amount);
+                        paid += stake_infos[staker].withdraw(&mut pool, amount, &clock(slot)).unwrap();
+                    }
+                }
+            }
+
+            // everybody leaves, including stakers holding only settled reward
+            for stake_info in stake_infos.iter_mut() {
+                let amount = stake_info.amount;
+                paid += stake_info.withdraw(&mut pool, amount, &clock(slot)).unwrap();
+            }
+
+            prop_assert_eq!(pool.total_staked, 0);
+            prop_assert_eq!(pool.staker_count, 0);
+            prop_assert_eq!(pool.reward_owed, 0);
+            prop_assert_eq!(pool.distributed, expected);
+            prop_assert!(pool.distributed <= allocation);
+            prop_assert!(paid <= pool.distributed);
+            // integer division may leave at most one unit per settlement in the pool
+            let max_dust = (3 * (50 + STAKERS)) as u64;
+            prop_assert!(pool.distributed - paid <= max_dust);
+        }
+    }
 }
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+// tokens requested out of a pool, held until unlock_at
+// a staker can have several tickets, each with its own id
+#[account]
+#[derive(InitSpace)]
+pub struct UnbondingTicket {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub stake_mint: Pubkey,
+    pub id: u64,
+    // amount held by the ticket_ata, net of transfer fees
+    pub amount: u64,
+    // unix timestamp from which the tokens can be withdrawn
+    pub unlock_at: i64,
+}
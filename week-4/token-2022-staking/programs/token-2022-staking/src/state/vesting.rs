@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MyErrorCode, MAX_VESTING_TRANCHES};
+
+// reward locked by one claim, it vests linearly from its own start
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct VestingTranche {
+    // unix timestamp of the claim
+    pub start: i64,
+    // seconds after start before anything vests
+    pub cliff: u64,
+    // seconds after start when the whole total is vested
+    pub duration: u64,
+    pub total: u64,
+    // part of total already released
+    pub released: u64,
+}
+
+impl VestingTranche {
+    // part of total vested at `now`, linear from start once the cliff is over
+    pub fn vested(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.start).max(0) as u64;
+        if elapsed < self.cliff {
+            return Ok(0);
+        }
+        if elapsed >= self.duration {
+            return Ok(self.total);
+        }
+        let vested = (self.total as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(MyErrorCode::Overflow)?
+            / self.duration as u128;
+        u64::try_from(vested).map_err(|_| error!(MyErrorCode::Overflow))
+    }
+}
+
+// claimed reward of a staker locked under linear schedules, the tokens are held by its vesting_ata
+// every claim vests on its own schedule, a new claim never delays the reward locked before it
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub reward_mint: Pubkey,
+    // tranches not fully released yet, oldest first
+    #[max_len(MAX_VESTING_TRANCHES)]
+    pub tranches: Vec<VestingTranche>,
+    // vested in a tranche that was merged and not released yet
+    pub unlocked: u64,
+}
+
+impl Vesting {
+    // nothing is locked nor waiting to be released
+    pub fn is_empty(&self) -> bool {
+        self.tranches.is_empty() && self.unlocked == 0
+    }
+
+    pub fn releasable(&self, now: i64) -> Result<u64> {
+        self.tranches
+            .iter()
+            .try_fold(self.unlocked, |releasable, tranche| {
+                releasable
+                    .checked_add(tranche.vested(now)?.saturating_sub(tranche.released))
+                    .ok_or(error!(MyErrorCode::Overflow))
+            })
+    }
+
+    // lock `amount` more under the given schedule, starting now
+    // once every tranche is in use, the new amount joins the newest tranche and its locked part starts over
+    pub fn add(&mut self, amount: u64, now: i64, cliff: u64, duration: u64) -> Result<()> {
+        self.tranches
+            .retain(|tranche| tranche.released < tranche.total);
+        let mut total = amount;
+        if self.tranches.len() == MAX_VESTING_TRANCHES {
+            let newest = self.tranches.pop().ok_or(MyErrorCode::Overflow)?;
+            let vested = newest.vested(now)?;
+            self.unlocked = self
+                .unlocked
+                .checked_add(vested.saturating_sub(newest.released))
+                .ok_or(MyErrorCode::Overflow)?;
+            total = (newest.total - vested)
+                .checked_add(amount)
+                .ok_or(MyErrorCode::Overflow)?;
+        }
+        self.tranches.push(VestingTranche {
+            start: now,
+            cliff,
+            duration,
+            total,
+            released: 0,
+        });
+        Ok(())
+    }
+
+    // mark everything vested at `now` as released and return it
+    pub fn release(&mut self, now: i64) -> Result<u64> {
+        let amount = self.releasable(now)?;
+        for tranche in self.tranches.iter_mut() {
+            tranche.released = tranche.vested(now)?;
+        }
+        self.tranches
+            .retain(|tranche| tranche.released < tranche.total);
+        self.unlocked = 0;
+        Ok(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_vesting() -> Vesting {
+        Vesting {
+            pool: Pubkey::default(),
+            staker: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            tranches: vec![],
+            unlocked: 0,
+        }
+    }
+
+    #[test]
+    fn vests_linearly_after_cliff() {
+        let mut vesting = new_vesting();
+        vesting.add(1_000, 100, 10, 100).unwrap();
+
+        assert_eq!(vesting.releasable(109).unwrap(), 0);
+        assert_eq!(vesting.releasable(110).unwrap(), 100);
+        assert_eq!(vesting.release(150).unwrap(), 500);
+        assert_eq!(vesting.release(150).unwrap(), 0);
+        assert_eq!(vesting.release(1_000).unwrap(), 500);
+        assert!(vesting.is_empty());
+    }
+
+    #[test]
+    fn new_reward_keeps_the_schedule_of_the_locked_reward() {
+        let mut vesting = new_vesting();
+        vesting.add(1_000, 0, 0, 100).unwrap();
+        assert_eq!(vesting.release(20).unwrap(), 200);
+
+        // the first 1_000 still ends at 100, the new 500 vests from 50 to 150
+        vesting.add(500, 50, 0, 100).unwrap();
+        assert_eq!(vesting.releasable(50).unwrap(), 300);
+        assert_eq!(vesting.release(100).unwrap(), 800 + 250);
+        assert_eq!(vesting.tranches.len(), 1);
+        assert_eq!(vesting.release(150).unwrap(), 250);
+        assert!(vesting.tranches.is_empty());
+    }
+
+    #[test]
+    fn full_tranches_merge_into_the_newest_one() {
+        let mut vesting = new_vesting();
+        for i in 0..MAX_VESTING_TRANCHES as i64 {
+            vesting.add(100, i * 10, 0, 100).unwrap();
+        }
+
+        // the newest tranche started at 70, 30 of it is vested and stays releasable
+        let start = (MAX_VESTING_TRANCHES as i64 - 1) * 10;
+        vesting.add(100, start + 30, 0, 100).unwrap();
+        assert_eq!(vesting.tranches.len(), MAX_VESTING_TRANCHES);
+        assert_eq!(vesting.unlocked, 30);
+        assert_eq!(vesting.tranches.last().unwrap().total, 170);
+        // the older tranches keep their schedule
+        assert_eq!(vesting.tranches[0].vested(100).unwrap(), 100);
+
+        let total: u64 = (MAX_VESTING_TRANCHES as u64 + 1) * 100;
+        assert_eq!(vesting.release(start + 130).unwrap(), total);
+        assert!(vesting.tranches.is_empty());
+    }
+}
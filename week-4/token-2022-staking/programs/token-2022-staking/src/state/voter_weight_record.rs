@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+// action the voter weight was computed for, same layout as the spl-governance addin api
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
+
+// voter weight record of the spl-governance addin api, read by spl-governance when the realm uses
+// this program as its voter weight addin. the anchor discriminator of `VoterWeightRecord` is the one
+// expected by the addin api, so the field order must not change
+#[account]
+#[derive(InitSpace)]
+pub struct VoterWeightRecord {
+    pub realm: Pubkey,
+    pub governing_token_mint: Pubkey,
+    // the staker
+    pub governing_token_owner: Pubkey,
+    pub voter_weight: u64,
+    // slot the weight is valid for, spl-governance rejects it in any later slot
+    pub voter_weight_expiry: Option<u64>,
+    pub weight_action: Option<VoterWeightAction>,
+    pub weight_action_target: Option<Pubkey>,
+    pub reserved: [u8; 8],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn layout_matches_the_addin_api() {
+        assert_eq!(
+            VoterWeightRecord::DISCRIMINATOR,
+            [46, 249, 155, 75, 153, 248, 116, 9]
+        );
+        assert_eq!(8 + VoterWeightRecord::INIT_SPACE, 164);
+    }
+}
@@ -0,0 +1,1998 @@
+use anchor_lang::{
+    solana_program::instruction::{AccountMeta, Instruction},
+    AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::{self, instruction::create_associated_token_account},
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            metadata_pointer::MetadataPointer, transfer_hook, BaseStateWithExtensions,
+            ExtensionType, StateWithExtensions,
+        },
+        state::{Account as TokenAccountState, Mint as MintState},
+    },
+    token_interface::spl_token_metadata_interface::state::TokenMetadata,
+};
+use solana_program_test::{find_file, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use token_2022_staking::{
+    error::MyErrorCode, Config, EmissionMode, MetadataField, Pool, ReceiptMode, RewardSource,
+    StakeInfo, StakeMode, Vesting, VoterWeightRecord, CONFIG_SEED, POOL_SEED, RECEIPT_SEED,
+    SHARE_SEED, STAKEINFO_SEED, UNBONDING_SEED, VESTING_SEED, VOTER_WEIGHT_SEED,
+};
+
+const CONFIG_ID: u64 = 1;
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+const STAKER_BALANCE: u64 = 1_000 * ONE_TOKEN;
+const STAKE_AMOUNT: u64 = 100 * ONE_TOKEN;
+const ALLOCATION: u64 = 1_000_000 * ONE_TOKEN;
+// reward of the whole pool per slot
+const REWARD_RATE: u64 = ONE_TOKEN;
+
+const NAME: &str = "Staking Reward";
+const SYMBOL: &str = "RWD";
+const URI: &str = "https://example.com/reward.json";
+
+const PROGRAM_FILE: &str = "token_2022_staking.so";
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: token_2022_staking::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &token_2022_staking::ID).0
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &token_2022_staking::ID).0
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::ID)
+}
+
+// every mint of the suite is a Token-2022 mint
+struct TestEnv {
+    context: ProgramTestContext,
+    reward_mint: Pubkey,
+    stake_mint: Pubkey,
+    staker: Keypair,
+}
+
+impl TestEnv {
+    // a config, a funded staker and a pool minting the reward of the config
+    async fn new() -> Option<Self> {
+        let mut env = Self::without_pool().await?;
+        let stake_mint = env.stake_mint;
+        env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+            .await
+            .unwrap();
+        Some(env)
+    }
+
+    // the program built by `anchor build` is found through SBF_OUT_DIR, set in .cargo/config.toml
+    async fn without_pool() -> Option<Self> {
+        if find_file(PROGRAM_FILE).is_none() {
+            eprintln!("skipped: {PROGRAM_FILE} is missing, run `anchor build` before `cargo test`");
+            return None;
+        }
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(true);
+        program_test.add_program("token_2022_staking", token_2022_staking::ID, None);
+        let context = program_test.start_with_context().await;
+
+        let mut env = Self {
+            context,
+            reward_mint: Pubkey::default(),
+            stake_mint: Pubkey::default(),
+            staker: Keypair::new(),
+        };
+
+        let reward_mint = Keypair::new();
+        env.initialize(&reward_mint).await.unwrap();
+        env.reward_mint = reward_mint.pubkey();
+
+        env.stake_mint = env.create_mint(&[]).await;
+        let staker = env.staker.insecure_clone();
+        env.fund_wallet(&staker).await;
+        let stake_mint = env.stake_mint;
+        env.mint_to(&stake_mint, &staker.pubkey(), STAKER_BALANCE)
+            .await;
+
+        Some(env)
+    }
+
+    fn payer(&self) -> Keypair {
+        self.context.payer.insecure_clone()
+    }
+
+    fn config(&self) -> Pubkey {
+        pda(&[CONFIG_SEED, CONFIG_ID.to_le_bytes().as_ref()])
+    }
+
+    fn pool(&self) -> Pubkey {
+        pda(&[POOL_SEED, self.config().as_ref(), self.stake_mint.as_ref()])
+    }
+
+    fn stake_info(&self, staker: &Pubkey) -> Pubkey {
+        pda(&[STAKEINFO_SEED, self.pool().as_ref(), staker.as_ref()])
+    }
+
+    fn vesting(&self, staker: &Pubkey) -> Pubkey {
+        pda(&[VESTING_SEED, self.pool().as_ref(), staker.as_ref()])
+    }
+
+    fn ticket(&self, staker: &Pubkey, id: u64) -> Pubkey {
+        pda(&[
+            UNBONDING_SEED,
+            self.pool().as_ref(),
+            staker.as_ref(),
+            id.to_le_bytes().as_ref(),
+        ])
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    // the payer is the mint authority, NonTransferable and TransferHook are the only extensions
+    // initialized here, the hook points to a program that is never called
+    async fn create_mint(&mut self, extensions: &[ExtensionType]) -> Pubkey {
+        let payer = self.payer();
+        let mint = Keypair::new();
+
+        let space = ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        )];
+        if extensions.contains(&ExtensionType::NonTransferable) {
+            instructions.push(
+                spl_token_2022::instruction::initialize_non_transferable_mint(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                )
+                .unwrap(),
+            );
+        }
+        if extensions.contains(&ExtensionType::TransferHook) {
+            instructions.push(
+                transfer_hook::instruction::initialize(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    None,
+                    Some(Pubkey::new_unique()),
+                )
+                .unwrap(),
+            );
+        }
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        );
+
+        self.process(&instructions, &[&mint]).await.unwrap();
+
+        mint.pubkey()
+    }
+
+    // creates the associated token account of the owner if needed
+    async fn create_ata(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let address = ata(owner, mint);
+        if !self.account_exists(address).await {
+            let payer = self.payer();
+            let instruction =
+                create_associated_token_account(&payer.pubkey(), owner, mint, &spl_token_2022::ID);
+            self.process(&[instruction], &[]).await.unwrap();
+        }
+        address
+    }
+
+    async fn mint_to(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let token_account = self.create_ata(owner, mint).await;
+        let payer = self.payer();
+        let instruction = spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            mint,
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn fund_wallet(&mut self, wallet: &Keypair) {
+        let payer = self.payer();
+        let instruction =
+            system_instruction::transfer(&payer.pubkey(), &wallet.pubkey(), 1_000_000_000);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn initialize(&mut self, mint: &Keypair) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        let instruction = instruction(
+            token_2022_staking::accounts::Initialize {
+                signer: payer.pubkey(),
+                config: self.config(),
+                mint: mint.pubkey(),
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+                rent: solana_sdk::sysvar::rent::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::Initialize {
+                id: CONFIG_ID,
+                name: NAME.to_string(),
+                symbol: SYMBOL.to_string(),
+                uri: URI.to_string(),
+                decimals: DECIMALS,
+                additional_metadata: vec![MetadataField {
+                    key: "website".to_string(),
+                    value: "https://example.com".to_string(),
+                }],
+            },
+        );
+
+        self.process(&[instruction], &[mint]).await
+    }
+
+    // a reward mint other than the reward mint of the config is deposited from the payer
+    fn create_pool_instruction(
+        &self,
+        reward_mint: Pubkey,
+        funder_reward_ata: Option<Pubkey>,
+        allocation: u64,
+        reward_rate: u64,
+        emission_end: u64,
+    ) -> Instruction {
+        instruction(
+            token_2022_staking::accounts::CreatePool {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                stake_mint: self.stake_mint,
+                pool: self.pool(),
+                reward_mint,
+                funder_reward_ata,
+                reward_ata: ata(&self.pool(), &reward_mint),
+                associated_token_program: spl_associated_token_account::ID,
+                reward_token_program: spl_token_2022::ID,
+                stake_token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::CreatePool {
+                allocation,
+                reward_rate,
+                emission_mode: EmissionMode::Slot,
+                emission_end,
+            },
+        )
+    }
+
+    // pool of stake_mint minting the reward of the config
+    async fn create_pool(
+        &mut self,
+        stake_mint: Pubkey,
+        allocation: u64,
+        reward_rate: u64,
+        emission_end: u64,
+    ) -> Result<(), BanksClientError> {
+        self.stake_mint = stake_mint;
+        let instruction = self.create_pool_instruction(
+            self.reward_mint,
+            None,
+            allocation,
+            reward_rate,
+            emission_end,
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    // the reward of the pool is minted, no funder is needed
+    async fn top_up(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let pool = self.pool();
+        let instruction = instruction(
+            token_2022_staking::accounts::TopUp {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool,
+                reward_mint: self.reward_mint,
+                reward_ata: ata(&pool, &self.reward_mint),
+                funder_reward_ata: None,
+                reward_token_program: spl_token_2022::ID,
+            },
+            token_2022_staking::instruction::TopUp { amount },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    // pool of the stake mint rewarding the stake mint, deposited by the payer
+    async fn create_liquid_pool(&mut self) {
+        let payer = self.payer().pubkey();
+        let stake_mint = self.stake_mint;
+        self.mint_to(&stake_mint, &payer, ALLOCATION).await;
+        let instruction = self.create_pool_instruction(
+            stake_mint,
+            Some(ata(&payer, &stake_mint)),
+            ALLOCATION,
+            REWARD_RATE,
+            u64::MAX,
+        );
+        self.process(&[instruction], &[]).await.unwrap();
+        self.enable_liquid_staking(stake_mint).await.unwrap();
+    }
+
+    async fn enable_liquid_staking(&mut self, stake_mint: Pubkey) -> Result<(), BanksClientError> {
+        let instruction = instruction(
+            token_2022_staking::accounts::EnableLiquidStaking {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool: self.pool(),
+                stake_mint,
+                share_mint: pda(&[SHARE_SEED, self.pool().as_ref()]),
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            },
+            token_2022_staking::instruction::EnableLiquidStaking {},
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn liquid_stake(
+        &mut self,
+        staker: &Keypair,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let share_mint = pda(&[SHARE_SEED, self.pool().as_ref()]);
+        let instruction = instruction(
+            token_2022_staking::accounts::LiquidStake {
+                signer: staker.pubkey(),
+                pool: self.pool(),
+                stake_mint: self.stake_mint,
+                share_mint,
+                staker_ata: ata(&staker.pubkey(), &self.stake_mint),
+                reward_ata: ata(&self.pool(), &self.stake_mint),
+                staker_share_ata: ata(&staker.pubkey(), &share_mint),
+                stake_token_program: spl_token_2022::ID,
+                share_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::LiquidStake { amount },
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    async fn stake(&mut self, staker: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+        let stake_info = self.stake_info(&staker.pubkey());
+        let instruction = instruction(
+            token_2022_staking::accounts::Stake {
+                signer: staker.pubkey(),
+                pool: self.pool(),
+                stake_mint: self.stake_mint,
+                stake_info,
+                staker_ata: ata(&staker.pubkey(), &self.stake_mint),
+                stake_info_ata: ata(&stake_info, &self.stake_mint),
+                receipt_mint: None,
+                staker_receipt_ata: None,
+                receipt_token_program: None,
+                stake_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::Stake { amount },
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    // extra_reward_accounts are passed in remaining_accounts
+    async fn unstake(
+        &mut self,
+        staker: &Keypair,
+        amount: u64,
+        extra_reward_accounts: Vec<AccountMeta>,
+    ) -> Result<(), BanksClientError> {
+        let stake_info = self.stake_info(&staker.pubkey());
+        let pool = self.pool();
+        let mut instruction = instruction(
+            token_2022_staking::accounts::Unstake {
+                signer: staker.pubkey(),
+                pool,
+                stake_mint: self.stake_mint,
+                stake_info,
+                staker_ata: ata(&staker.pubkey(), &self.stake_mint),
+                stake_info_ata: ata(&stake_info, &self.stake_mint),
+                reward_mint: self.reward_mint,
+                reward_ata: ata(&pool, &self.reward_mint),
+                staker_reward_ata: ata(&staker.pubkey(), &self.reward_mint),
+                vesting: None,
+                vesting_ata: None,
+                receipt_mint: None,
+                staker_receipt_ata: None,
+                receipt_token_program: None,
+                stake_token_program: spl_token_2022::ID,
+                reward_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::Unstake { amount },
+        );
+        instruction.accounts.extend(extra_reward_accounts);
+        self.process(&[instruction], &[staker]).await
+    }
+
+    // with_vesting passes the vesting accounts of the staker
+    async fn claim(
+        &mut self,
+        signer: &Keypair,
+        staker: &Pubkey,
+        with_vesting: bool,
+        extra_reward_accounts: Vec<AccountMeta>,
+    ) -> Result<(), BanksClientError> {
+        let pool = self.pool();
+        let vesting = self.vesting(staker);
+        let stake_info = self.stake_info(staker);
+        let mut instruction = instruction(
+            token_2022_staking::accounts::Claim {
+                signer: signer.pubkey(),
+                staker: *staker,
+                pool,
+                stake_info,
+                stake_info_ata: ata(&stake_info, &self.stake_mint),
+                stake_mint: self.stake_mint,
+                reward_mint: self.reward_mint,
+                reward_ata: ata(&pool, &self.reward_mint),
+                staker_reward_ata: ata(staker, &self.reward_mint),
+                vesting: with_vesting.then_some(vesting),
+                vesting_ata: with_vesting.then(|| ata(&vesting, &self.reward_mint)),
+                stake_token_program: spl_token_2022::ID,
+                reward_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::Claim {},
+        );
+        instruction.accounts.extend(extra_reward_accounts);
+        self.process(&[instruction], &[signer]).await
+    }
+
+    async fn release_vested(
+        &mut self,
+        staker: &Keypair,
+        reward_mint: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let vesting = self.vesting(&staker.pubkey());
+        let instruction = instruction(
+            token_2022_staking::accounts::ReleaseVested {
+                signer: staker.pubkey(),
+                vesting,
+                reward_mint,
+                vesting_ata: ata(&vesting, &self.reward_mint),
+                staker_reward_ata: ata(&staker.pubkey(), &reward_mint),
+                reward_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            },
+            token_2022_staking::instruction::ReleaseVested {},
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    async fn request_unstake(
+        &mut self,
+        staker: &Keypair,
+        id: u64,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let stake_info = self.stake_info(&staker.pubkey());
+        let ticket = self.ticket(&staker.pubkey(), id);
+        let instruction = instruction(
+            token_2022_staking::accounts::RequestUnstake {
+                signer: staker.pubkey(),
+                pool: self.pool(),
+                stake_mint: self.stake_mint,
+                stake_info,
+                stake_info_ata: ata(&stake_info, &self.stake_mint),
+                ticket,
+                ticket_ata: ata(&ticket, &self.stake_mint),
+                receipt_mint: None,
+                staker_receipt_ata: None,
+                receipt_token_program: None,
+                stake_token_program: spl_token_2022::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::RequestUnstake { id, amount },
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    async fn withdraw_unbonded(
+        &mut self,
+        staker: &Keypair,
+        id: u64,
+    ) -> Result<(), BanksClientError> {
+        let ticket = self.ticket(&staker.pubkey(), id);
+        let stake_info = self.stake_info(&staker.pubkey());
+        let instruction = instruction(
+            token_2022_staking::accounts::WithdrawUnbonded {
+                signer: staker.pubkey(),
+                ticket,
+                stake_info,
+                stake_info_ata: ata(&stake_info, &self.stake_mint),
+                stake_mint: self.stake_mint,
+                ticket_ata: ata(&ticket, &self.stake_mint),
+                staker_ata: ata(&staker.pubkey(), &self.stake_mint),
+                stake_token_program: spl_token_2022::ID,
+                event_authority: event_authority(),
+                program: token_2022_staking::ID,
+            },
+            token_2022_staking::instruction::WithdrawUnbonded {},
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    async fn set_pool_paused(&mut self, paused: bool) -> Result<(), BanksClientError> {
+        let instruction = instruction(
+            token_2022_staking::accounts::SetPoolPaused {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool: self.pool(),
+            },
+            token_2022_staking::instruction::SetPoolPaused { paused },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn set_unbonding_period(
+        &mut self,
+        unbonding_period: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction(
+            token_2022_staking::accounts::SetUnbondingPeriod {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool: self.pool(),
+            },
+            token_2022_staking::instruction::SetUnbondingPeriod { unbonding_period },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn set_vesting_schedule(
+        &mut self,
+        cliff: u64,
+        duration: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction(
+            token_2022_staking::accounts::SetVestingSchedule {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool: self.pool(),
+            },
+            token_2022_staking::instruction::SetVestingSchedule { cliff, duration },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    // a deposited reward goes back to the payer
+    async fn close_pool(&mut self) -> Result<(), BanksClientError> {
+        let pool = self.pool();
+        let payer = self.payer().pubkey();
+        let Pool {
+            reward_mint,
+            reward_source,
+            ..
+        } = self.pool_account().await;
+        let instruction = instruction(
+            token_2022_staking::accounts::ClosePool {
+                signer: payer,
+                config: self.config(),
+                pool,
+                reward_mint,
+                reward_ata: ata(&pool, &reward_mint),
+                funder_reward_ata: (reward_source == RewardSource::Deposited)
+                    .then(|| ata(&payer, &reward_mint)),
+                reward_token_program: spl_token_2022::ID,
+            },
+            token_2022_staking::instruction::ClosePool {},
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn create_receipt_mint(
+        &mut self,
+        receipt_mode: ReceiptMode,
+    ) -> Result<(), BanksClientError> {
+        let pool = self.pool();
+        let instruction = instruction(
+            token_2022_staking::accounts::CreateReceiptMint {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool,
+                stake_mint: self.stake_mint,
+                receipt_mint: pda(&[RECEIPT_SEED, pool.as_ref()]),
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            },
+            token_2022_staking::instruction::CreateReceiptMint { receipt_mode },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    // a new mint deposited by the payer as an extra reward of the pool
+    async fn add_extra_reward(&mut self, reward_rate: u64) -> Result<Pubkey, BanksClientError> {
+        let reward_mint = self.create_mint(&[]).await;
+        self.add_extra_reward_mint(reward_mint, reward_rate).await?;
+        Ok(reward_mint)
+    }
+
+    async fn add_extra_reward_mint(
+        &mut self,
+        reward_mint: Pubkey,
+        reward_rate: u64,
+    ) -> Result<(), BanksClientError> {
+        let payer = self.payer().pubkey();
+        self.mint_to(&reward_mint, &payer, ALLOCATION).await;
+        let pool = self.pool();
+        let instruction = instruction(
+            token_2022_staking::accounts::AddExtraReward {
+                signer: payer,
+                config: self.config(),
+                pool,
+                reward_mint,
+                reward_ata: ata(&pool, &reward_mint),
+                funder_reward_ata: ata(&payer, &reward_mint),
+                associated_token_program: spl_associated_token_account::ID,
+                reward_token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            },
+            token_2022_staking::instruction::AddExtraReward {
+                allocation: ALLOCATION,
+                reward_rate,
+            },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    // remaining_accounts paying an extra reward to the staker
+    async fn extra_reward_accounts(
+        &mut self,
+        staker: &Pubkey,
+        reward_mint: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        let destination = self.create_ata(staker, reward_mint).await;
+        vec![
+            AccountMeta::new_readonly(*reward_mint, false),
+            AccountMeta::new(ata(&self.pool(), reward_mint), false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ]
+    }
+
+    async fn set_governance(
+        &mut self,
+        realm: Pubkey,
+        vote_lock_period: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction(
+            token_2022_staking::accounts::SetGovernance {
+                signer: self.payer().pubkey(),
+                config: self.config(),
+                pool: self.pool(),
+            },
+            token_2022_staking::instruction::SetGovernance {
+                realm,
+                governing_token_mint: self.stake_mint,
+                vote_lock_period,
+            },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn update_voter_weight_record(
+        &mut self,
+        staker: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let pool = self.pool();
+        let instruction = instruction(
+            token_2022_staking::accounts::UpdateVoterWeightRecord {
+                signer: staker.pubkey(),
+                pool,
+                stake_info: self.stake_info(&staker.pubkey()),
+                voter_weight_record: pda(&[
+                    VOTER_WEIGHT_SEED,
+                    pool.as_ref(),
+                    staker.pubkey().as_ref(),
+                ]),
+                system_program: system_program::ID,
+            },
+            token_2022_staking::instruction::UpdateVoterWeightRecord {},
+        );
+        self.process(&[instruction], &[staker]).await
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn pool_account(&mut self) -> Pool {
+        let pool = self.pool();
+        self.account(pool).await
+    }
+
+    async fn token_balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // the extensions of a Token-2022 account follow the base layout
+        TokenAccountState::unpack_from_slice(&account.data[..TokenAccountState::LEN])
+            .unwrap()
+            .amount
+    }
+
+    // overwrite the balance of a token account, to put the pool in a state no instruction leads to
+    async fn set_token_balance(&mut self, token_account: Pubkey, amount: u64) {
+        let mut account = self
+            .context
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut state =
+            TokenAccountState::unpack_from_slice(&account.data[..TokenAccountState::LEN]).unwrap();
+        state.amount = amount;
+        state.pack_into_slice(&mut account.data[..TokenAccountState::LEN]);
+        self.context.set_account(&token_account, &account.into());
+    }
+
+    async fn account_exists(&mut self, address: Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    async fn current_slot(&mut self) -> u64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .slot
+    }
+
+    async fn advance_time(&mut self, seconds: i64) {
+        let mut clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    // the emission clock of the pool is the slot, last_update is the slot of the last update
+    async fn warp_slots(&mut self, slots: u64) -> u64 {
+        let from = self.pool_account().await.last_update;
+        self.context.warp_to_slot(from + slots).unwrap();
+        from + slots
+    }
+}
+
+fn assert_error(result: Result<(), BanksClientError>, error: MyErrorCode) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+    );
+}
+
+#[tokio::test]
+async fn initialize_creates_reward_mint_with_metadata() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let config: Config = env.account(env.config()).await;
+    assert_eq!(config.id, CONFIG_ID);
+    assert_eq!(config.authority, env.payer().pubkey());
+    assert_eq!(config.reward_mint, env.reward_mint);
+
+    let account = env
+        .context
+        .banks_client
+        .get_account(env.reward_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, spl_token_2022::ID);
+
+    let mint = StateWithExtensions::<MintState>::unpack(&account.data).unwrap();
+    assert_eq!(mint.base.decimals, DECIMALS);
+    assert_eq!(mint.base.supply, 0);
+    assert_eq!(
+        Option::<Pubkey>::from(mint.base.mint_authority),
+        Some(env.config())
+    );
+
+    // the metadata lives in the mint itself and the config can update it
+    let pointer = mint.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.authority),
+        Some(env.config())
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.metadata_address),
+        Some(env.reward_mint)
+    );
+
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.mint, env.reward_mint);
+    assert_eq!(
+        Option::<Pubkey>::from(metadata.update_authority),
+        Some(env.config())
+    );
+    assert_eq!(metadata.name, NAME);
+    assert_eq!(metadata.symbol, SYMBOL);
+    assert_eq!(metadata.uri, URI);
+    assert_eq!(
+        metadata.additional_metadata,
+        vec![("website".to_string(), "https://example.com".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn create_pool_mints_allocation_to_reward_ata() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    let pool = env.pool_account().await;
+    assert_eq!(pool.config, env.config());
+    assert_eq!(pool.stake_mint, env.stake_mint);
+    assert_eq!(pool.reward_mint, env.reward_mint);
+    assert_eq!(pool.reward_ata, ata(&env.pool(), &env.reward_mint));
+    assert_eq!(pool.allocation, ALLOCATION);
+    assert_eq!(pool.reward_rate, REWARD_RATE);
+    assert_eq!(pool.total_staked, 0);
+    assert_eq!(pool.stake_mode, StakeMode::Position);
+    assert_eq!(pool.last_update, env.current_slot().await);
+
+    assert_eq!(env.token_balance(pool.reward_ata).await, ALLOCATION);
+    let account = env
+        .context
+        .banks_client
+        .get_account(env.reward_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let mint = StateWithExtensions::<MintState>::unpack(&account.data).unwrap();
+    assert_eq!(mint.base.supply, ALLOCATION);
+}
+
+#[tokio::test]
+async fn stake_transfers_tokens_to_stake_info_ata() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.staker, staker.pubkey());
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+
+    let pool = env.pool_account().await;
+    assert_eq!(pool.total_staked, STAKE_AMOUNT);
+    assert_eq!(pool.staker_count, 1);
+
+    let stake_info_ata = ata(&env.stake_info(&staker.pubkey()), &env.stake_mint);
+    let staker_ata = ata(&staker.pubkey(), &env.stake_mint);
+    assert_eq!(env.token_balance(stake_info_ata).await, STAKE_AMOUNT);
+    assert_eq!(
+        env.token_balance(staker_ata).await,
+        STAKER_BALANCE - STAKE_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn unstake_pays_reward_for_warped_slots() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let slots_passed = 250;
+    env.warp_slots(slots_passed).await;
+
+    env.unstake(&staker, STAKE_AMOUNT / 2, vec![])
+        .await
+        .unwrap();
+
+    // the only staker earns the whole emission
+    let reward = slots_passed * REWARD_RATE;
+    let staker_reward_ata = ata(&staker.pubkey(), &env.reward_mint);
+    let reward_ata = ata(&env.pool(), &env.reward_mint);
+    assert_eq!(env.token_balance(staker_reward_ata).await, reward);
+    assert_eq!(env.token_balance(reward_ata).await, ALLOCATION - reward);
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.stake_mint))
+            .await,
+        STAKER_BALANCE - STAKE_AMOUNT / 2
+    );
+
+    let pool = env.pool_account().await;
+    assert_eq!(pool.distributed, reward);
+    assert_eq!(pool.total_staked, STAKE_AMOUNT / 2);
+    // a partial unstake keeps the position open
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.amount, STAKE_AMOUNT / 2);
+}
+
+#[tokio::test]
+async fn reward_is_shared_by_stake() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let first = env.staker.insecure_clone();
+    let second = Keypair::new();
+    env.fund_wallet(&second).await;
+    let stake_mint = env.stake_mint;
+    env.mint_to(&stake_mint, &second.pubkey(), STAKER_BALANCE)
+        .await;
+
+    env.stake(&first, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(100).await;
+    // the second staker stakes three times the first one
+    env.stake(&second, 3 * STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(200).await;
+
+    env.unstake(&first, STAKE_AMOUNT, vec![]).await.unwrap();
+    env.unstake(&second, 3 * STAKE_AMOUNT, vec![])
+        .await
+        .unwrap();
+
+    // 100 slots alone, then a quarter of 200 slots, the second staker earns the rest
+    let first_reward = 100 * REWARD_RATE + 200 * REWARD_RATE / 4;
+    let total_reward = env.pool_account().await.distributed;
+    assert_eq!(
+        env.token_balance(ata(&first.pubkey(), &env.reward_mint))
+            .await,
+        first_reward
+    );
+    assert_eq!(
+        env.token_balance(ata(&second.pubkey(), &env.reward_mint))
+            .await,
+        total_reward - first_reward
+    );
+    assert!(total_reward >= 300 * REWARD_RATE);
+}
+
+#[tokio::test]
+async fn full_unstake_closes_stake_info() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    env.unstake(&staker, STAKE_AMOUNT, vec![]).await.unwrap();
+
+    let stake_info = env.stake_info(&staker.pubkey());
+    assert!(!env.account_exists(stake_info).await);
+    assert!(!env.account_exists(ata(&stake_info, &env.stake_mint)).await);
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.stake_mint))
+            .await,
+        STAKER_BALANCE
+    );
+    let pool = env.pool_account().await;
+    assert_eq!(pool.total_staked, 0);
+    assert_eq!(pool.staker_count, 0);
+
+    // the position can be opened again after closing
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let stake_info: StakeInfo = env.account(stake_info).await;
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+}
+
+#[tokio::test]
+async fn claim_pays_reward_and_extra_reward() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let extra_mint = env.add_extra_reward(2 * REWARD_RATE).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let slots_passed = 50;
+    env.warp_slots(slots_passed).await;
+
+    let accounts = env
+        .extra_reward_accounts(&staker.pubkey(), &extra_mint)
+        .await;
+    env.claim(&staker, &staker.pubkey(), false, accounts)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        slots_passed * REWARD_RATE
+    );
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &extra_mint)).await,
+        slots_passed * 2 * REWARD_RATE
+    );
+    // the stake is untouched
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+}
+
+#[tokio::test]
+async fn unbonding_stake_is_withdrawn_after_the_period() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let unbonding_period = 3_600;
+    env.set_unbonding_period(unbonding_period).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    env.request_unstake(&staker, 0, STAKE_AMOUNT / 2)
+        .await
+        .unwrap();
+    env.request_unstake(&staker, 1, STAKE_AMOUNT / 2)
+        .await
+        .unwrap();
+    // the stake_info stays open for the reward earned before the request
+    let stake_info_address = env.stake_info(&staker.pubkey());
+    let stake_info: StakeInfo = env.account(stake_info_address).await;
+    assert_eq!(stake_info.amount, 0);
+    assert_eq!(stake_info.unbonding_count, 2);
+    assert_eq!(env.pool_account().await.total_staked, 0);
+    env.claim(&staker, &staker.pubkey(), false, vec![])
+        .await
+        .unwrap();
+
+    env.advance_time(unbonding_period as i64).await;
+    env.withdraw_unbonded(&staker, 0).await.unwrap();
+    assert!(!env.account_exists(env.ticket(&staker.pubkey(), 0)).await);
+    // the second ticket keeps the position open
+    let stake_info: StakeInfo = env.account(stake_info_address).await;
+    assert_eq!(stake_info.unbonding_count, 1);
+
+    // the last ticket of an empty position closes it
+    env.withdraw_unbonded(&staker, 1).await.unwrap();
+    assert!(!env.account_exists(stake_info_address).await);
+    assert!(
+        !env.account_exists(ata(&stake_info_address, &env.stake_mint))
+            .await
+    );
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.stake_mint))
+            .await,
+        STAKER_BALANCE
+    );
+}
+
+#[tokio::test]
+async fn claim_closes_the_position_left_by_unbonding() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_unbonding_period(3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let slots_passed = 10;
+    env.warp_slots(slots_passed).await;
+    env.request_unstake(&staker, 0, STAKE_AMOUNT).await.unwrap();
+    env.advance_time(3_600).await;
+    env.withdraw_unbonded(&staker, 0).await.unwrap();
+
+    // the reward earned before the request keeps the position open
+    let stake_info_address = env.stake_info(&staker.pubkey());
+    let stake_info: StakeInfo = env.account(stake_info_address).await;
+    assert_eq!(stake_info.unbonding_count, 0);
+    assert_eq!(stake_info.reward, slots_passed * REWARD_RATE);
+
+    env.claim(&staker, &staker.pubkey(), false, vec![])
+        .await
+        .unwrap();
+    assert!(!env.account_exists(stake_info_address).await);
+    assert!(
+        !env.account_exists(ata(&stake_info_address, &env.stake_mint))
+            .await
+    );
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        slots_passed * REWARD_RATE
+    );
+    assert_eq!(env.pool_account().await.reward_owed, 0);
+}
+
+#[tokio::test]
+async fn unstake_keeps_stake_info_open_while_unbonding() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_unbonding_period(3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.request_unstake(&staker, 0, STAKE_AMOUNT / 2)
+        .await
+        .unwrap();
+    // the rest leaves right away once the pool has no unbonding period anymore
+    env.set_unbonding_period(0).await.unwrap();
+    env.unstake(&staker, STAKE_AMOUNT / 2, vec![])
+        .await
+        .unwrap();
+
+    let stake_info_address = env.stake_info(&staker.pubkey());
+    let stake_info: StakeInfo = env.account(stake_info_address).await;
+    assert_eq!(stake_info.amount, 0);
+    assert_eq!(stake_info.unbonding_count, 1);
+
+    env.advance_time(3_600).await;
+    env.withdraw_unbonded(&staker, 0).await.unwrap();
+    assert!(!env.account_exists(stake_info_address).await);
+}
+
+#[tokio::test]
+async fn stake_zero_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    let result = env.stake(&staker, 0).await;
+    assert_error(result, MyErrorCode::InvalidDepositAmount);
+}
+
+#[tokio::test]
+async fn enable_liquid_staking_with_wrong_stake_mint_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    let wrong_mint = env.create_mint(&[]).await;
+    let result = env.enable_liquid_staking(wrong_mint).await;
+    assert_error(result, MyErrorCode::InvalidStakeMintAccount);
+}
+
+#[tokio::test]
+async fn create_pool_without_allocation_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let stake_mint = env.stake_mint;
+    let result = env.create_pool(stake_mint, 0, REWARD_RATE, u64::MAX).await;
+    assert_error(result, MyErrorCode::AllocationMustBeGreaterThanZero);
+}
+
+#[tokio::test]
+async fn create_pool_without_reward_rate_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let stake_mint = env.stake_mint;
+    let result = env.create_pool(stake_mint, ALLOCATION, 0, u64::MAX).await;
+    assert_error(result, MyErrorCode::RewardRateMustBeGreaterThanZero);
+}
+
+#[tokio::test]
+async fn invalid_unstake_amounts_leave_the_position_untouched() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    let result = env.unstake(&staker, 0, vec![]).await;
+    assert_error(result, MyErrorCode::InvalidUnstakeAmount);
+    let result = env.unstake(&staker, STAKE_AMOUNT + 1, vec![]).await;
+    assert_error(result, MyErrorCode::InsufficientStakeAmount);
+
+    let stake_info_address = env.stake_info(&staker.pubkey());
+    let stake_info: StakeInfo = env.account(stake_info_address).await;
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+    assert_eq!(
+        env.token_balance(ata(&stake_info_address, &env.stake_mint))
+            .await,
+        STAKE_AMOUNT
+    );
+    assert_eq!(env.pool_account().await.total_staked, STAKE_AMOUNT);
+
+    // the whole stake still leaves with its reward
+    env.unstake(&staker, STAKE_AMOUNT, vec![]).await.unwrap();
+    assert!(!env.account_exists(stake_info_address).await);
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        10 * REWARD_RATE
+    );
+}
+
+#[tokio::test]
+async fn reward_overflow_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let stake_mint = env.stake_mint;
+    env.create_pool(stake_mint, ALLOCATION, u64::MAX, u64::MAX)
+        .await
+        .unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(2).await;
+    // the emission of two slots doesn't fit in a u64
+    let result = env.stake(&staker, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::Overflow);
+}
+
+#[tokio::test]
+async fn top_up_of_an_exhausted_pool_only_pays_from_now_on() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let stake_mint = env.stake_mint;
+    let allocation = 10 * REWARD_RATE;
+    env.create_pool(stake_mint, allocation, REWARD_RATE, u64::MAX)
+        .await
+        .unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    // the allocation is used up after 10 slots, the other 40 accrue nothing
+    env.warp_slots(50).await;
+    env.top_up(ALLOCATION).await.unwrap();
+    let pool = env.pool_account().await;
+    assert_eq!(pool.distributed, allocation);
+    assert_eq!(pool.allocation, allocation + ALLOCATION);
+
+    env.warp_slots(20).await;
+    env.unstake(&staker, STAKE_AMOUNT, vec![]).await.unwrap();
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        allocation + 20 * REWARD_RATE
+    );
+}
+
+#[tokio::test]
+async fn create_pool_with_past_emission_end_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let stake_mint = env.stake_mint;
+    let emission_end = env.current_slot().await;
+    let result = env
+        .create_pool(stake_mint, ALLOCATION, REWARD_RATE, emission_end)
+        .await;
+    assert_error(result, MyErrorCode::InvalidEmissionEnd);
+}
+
+#[tokio::test]
+async fn claim_after_emission_end_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let stake_mint = env.stake_mint;
+    let emission_end = env.current_slot().await + 5;
+    env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, emission_end)
+        .await
+        .unwrap();
+
+    env.context.warp_to_slot(emission_end + 10).unwrap();
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.context.warp_to_slot(emission_end + 20).unwrap();
+
+    let result = env.claim(&staker, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::NoRewardToClaim);
+}
+
+#[tokio::test]
+async fn stake_in_paused_pool_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.set_pool_paused(true).await.unwrap();
+    let result = env.stake(&staker, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::PoolPaused);
+
+    env.set_pool_paused(false).await.unwrap();
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+}
+
+#[tokio::test]
+async fn close_pool_with_stake_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let result = env.close_pool().await;
+    assert_error(result, MyErrorCode::PoolNotEmpty);
+}
+
+#[tokio::test]
+async fn close_pool_waits_for_owed_rewards_and_recreation_makes_stake_info_stale() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_unbonding_period(3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    // the whole stake unbonds, the reward earned so far stays settled in the stake_info
+    env.request_unstake(&staker, 0, STAKE_AMOUNT).await.unwrap();
+    let pool = env.pool_account().await;
+    assert_eq!(pool.total_staked, 0);
+    assert_eq!(pool.reward_owed, 10 * REWARD_RATE);
+
+    let result = env.close_pool().await;
+    assert_error(result, MyErrorCode::RewardsOwed);
+
+    env.claim(&staker, &staker.pubkey(), false, vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        10 * REWARD_RATE
+    );
+    env.close_pool().await.unwrap();
+
+    // the pool is re-created at the same address, the stake_info left by the old one is stale
+    let stake_mint = env.stake_mint;
+    env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+        .await
+        .unwrap();
+    assert_eq!(env.pool_account().await.nonce, 1);
+    let result = env.claim(&staker, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::StaleStakeInfo);
+
+    // staking again binds it to the new pool
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.pool_nonce, 1);
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+    assert_eq!(stake_info.reward, 0);
+}
+
+#[tokio::test]
+async fn recreated_pool_reuses_the_receipt_and_share_mints() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let receipt_mint = pda(&[RECEIPT_SEED, env.pool().as_ref()]);
+    env.create_receipt_mint(ReceiptMode::NonTransferable)
+        .await
+        .unwrap();
+    env.close_pool().await.unwrap();
+    assert!(env.account_exists(receipt_mint).await);
+
+    // the receipt mint of the closed pool is soulbound, it can't serve liquid receipts
+    let stake_mint = env.stake_mint;
+    env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+        .await
+        .unwrap();
+    let result = env.create_receipt_mint(ReceiptMode::Liquid).await;
+    assert_error(result, MyErrorCode::InvalidReceiptMode);
+    env.create_receipt_mint(ReceiptMode::NonTransferable)
+        .await
+        .unwrap();
+    let pool = env.pool_account().await;
+    assert_eq!(pool.receipt_mode, ReceiptMode::NonTransferable);
+    assert_eq!(pool.receipt_mint, receipt_mint);
+
+    // the share mint of a closed liquid pool is reused the same way
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    env.create_liquid_pool().await;
+    env.close_pool().await.unwrap();
+    env.create_liquid_pool().await;
+    let pool = env.pool_account().await;
+    assert_eq!(pool.stake_mode, StakeMode::Liquid);
+    assert_eq!(pool.share_mint, pda(&[SHARE_SEED, env.pool().as_ref()]));
+}
+
+#[tokio::test]
+async fn create_pool_with_deposited_reward_without_funder_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let reward_mint = env.create_mint(&[]).await;
+    let instruction =
+        env.create_pool_instruction(reward_mint, None, ALLOCATION, REWARD_RATE, u64::MAX);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, MyErrorCode::MissingRewardFunder);
+}
+
+#[tokio::test]
+async fn add_extra_reward_over_maximum_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    env.add_extra_reward(REWARD_RATE).await.unwrap();
+    env.add_extra_reward(REWARD_RATE).await.unwrap();
+    let result = env.add_extra_reward(REWARD_RATE).await.map(|_| ());
+    assert_error(result, MyErrorCode::TooManyExtraRewards);
+}
+
+#[tokio::test]
+async fn claim_without_extra_reward_accounts_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.add_extra_reward(REWARD_RATE).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+
+    let result = env.claim(&staker, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::InvalidExtraRewardAccounts);
+}
+
+#[tokio::test]
+async fn create_pool_with_non_transferable_stake_mint_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    let stake_mint = env.create_mint(&[ExtensionType::NonTransferable]).await;
+    let result = env
+        .create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+        .await;
+    assert_error(result, MyErrorCode::UnsupportedStakeMintExtension);
+}
+
+#[tokio::test]
+async fn non_transferable_reward_mints_are_rejected() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    // a deposited reward the pool could never pay out
+    let reward_mint = env.create_mint(&[ExtensionType::NonTransferable]).await;
+    let payer = env.payer().pubkey();
+    env.mint_to(&reward_mint, &payer, ALLOCATION).await;
+    let instruction = env.create_pool_instruction(
+        reward_mint,
+        Some(ata(&payer, &reward_mint)),
+        ALLOCATION,
+        REWARD_RATE,
+        u64::MAX,
+    );
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, MyErrorCode::UnsupportedRewardMintExtension);
+
+    // the same mint is rejected as an extra reward
+    let stake_mint = env.stake_mint;
+    env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+        .await
+        .unwrap();
+    let result = env.add_extra_reward_mint(reward_mint, REWARD_RATE).await;
+    assert_error(result, MyErrorCode::UnsupportedRewardMintExtension);
+    assert!(env.pool_account().await.extra_rewards.is_empty());
+}
+
+#[tokio::test]
+async fn hooked_reward_mints_are_rejected() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+
+    // reward transfers carry no hook accounts, the unstake paying them would always fail
+    let reward_mint = env.create_mint(&[ExtensionType::TransferHook]).await;
+    let payer = env.payer().pubkey();
+    env.mint_to(&reward_mint, &payer, ALLOCATION).await;
+    let instruction = env.create_pool_instruction(
+        reward_mint,
+        Some(ata(&payer, &reward_mint)),
+        ALLOCATION,
+        REWARD_RATE,
+        u64::MAX,
+    );
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, MyErrorCode::UnsupportedRewardMintExtension);
+
+    let stake_mint = env.stake_mint;
+    env.create_pool(stake_mint, ALLOCATION, REWARD_RATE, u64::MAX)
+        .await
+        .unwrap();
+    let result = env.add_extra_reward_mint(reward_mint, REWARD_RATE).await;
+    assert_error(result, MyErrorCode::UnsupportedRewardMintExtension);
+}
+
+#[tokio::test]
+async fn create_receipt_mint_without_receipt_mode_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    let result = env.create_receipt_mint(ReceiptMode::None).await;
+    assert_error(result, MyErrorCode::InvalidReceiptMode);
+}
+
+#[tokio::test]
+async fn stake_without_receipt_accounts_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.create_receipt_mint(ReceiptMode::NonTransferable)
+        .await
+        .unwrap();
+    let result = env.stake(&staker, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::MissingReceiptAccounts);
+}
+
+#[tokio::test]
+async fn stake_in_liquid_pool_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.create_liquid_pool().await;
+
+    let result = env.stake(&staker, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::InvalidStakeMode);
+}
+
+#[tokio::test]
+async fn enable_liquid_staking_with_other_reward_mint_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    let stake_mint = env.stake_mint;
+    let result = env.enable_liquid_staking(stake_mint).await;
+    assert_error(result, MyErrorCode::LiquidRewardMintMismatch);
+}
+
+#[tokio::test]
+async fn liquid_stake_worth_less_than_a_share_fails() {
+    let Some(mut env) = TestEnv::without_pool().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.create_liquid_pool().await;
+
+    env.liquid_stake(&staker, STAKE_AMOUNT).await.unwrap();
+    // the emission raises the value of a share above one token unit
+    env.warp_slots(200).await;
+    let result = env.liquid_stake(&staker, 1).await;
+    assert_error(result, MyErrorCode::ZeroShares);
+}
+
+#[tokio::test]
+async fn unstake_with_unbonding_period_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_unbonding_period(3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let result = env.unstake(&staker, STAKE_AMOUNT, vec![]).await;
+    assert_error(result, MyErrorCode::UnbondingRequired);
+}
+
+#[tokio::test]
+async fn withdraw_unbonded_before_the_period_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_unbonding_period(3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.request_unstake(&staker, 0, STAKE_AMOUNT).await.unwrap();
+    let result = env.withdraw_unbonded(&staker, 0).await;
+    assert_error(result, MyErrorCode::UnbondingNotFinished);
+}
+
+#[tokio::test]
+async fn claim_by_other_signer_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let other = Keypair::new();
+    env.fund_wallet(&other).await;
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+
+    let result = env.claim(&other, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::InvalidClaimAuthority);
+}
+
+#[tokio::test]
+async fn set_vesting_schedule_with_cliff_after_duration_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    let result = env.set_vesting_schedule(20, 10).await;
+    assert_error(result, MyErrorCode::InvalidVestingSchedule);
+}
+
+#[tokio::test]
+async fn claim_without_vesting_accounts_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_vesting_schedule(0, 3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+
+    let result = env.claim(&staker, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::MissingVestingAccounts);
+}
+
+#[tokio::test]
+async fn release_vested_before_the_cliff_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_vesting_schedule(600, 3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    env.claim(&staker, &staker.pubkey(), true, vec![])
+        .await
+        .unwrap();
+
+    let reward_mint = env.reward_mint;
+    let result = env.release_vested(&staker, reward_mint).await;
+    assert_error(result, MyErrorCode::NothingToRelease);
+
+    // the whole reward is released once the schedule is over
+    env.advance_time(3_600).await;
+    env.release_vested(&staker, reward_mint).await.unwrap();
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        10 * REWARD_RATE
+    );
+}
+
+#[tokio::test]
+async fn new_claim_does_not_delay_the_vesting_reward() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let duration = 3_600;
+    env.set_vesting_schedule(0, duration).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    env.claim(&staker, &staker.pubkey(), true, vec![])
+        .await
+        .unwrap();
+    // half of the first claim has vested when the second one is made
+    env.warp_slots(10).await;
+    env.advance_time(duration as i64 / 2).await;
+    env.claim(&staker, &staker.pubkey(), true, vec![])
+        .await
+        .unwrap();
+
+    let vesting: Vesting = env.account(env.vesting(&staker.pubkey())).await;
+    assert_eq!(vesting.tranches.len(), 2);
+    assert_eq!(vesting.tranches[0].total, 10 * REWARD_RATE);
+    assert_eq!(vesting.tranches[1].total, 10 * REWARD_RATE);
+
+    // the first claim is fully vested at the end of its own schedule
+    let first_end = vesting.tranches[0].start + duration as i64;
+    let now = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    env.advance_time(first_end - now).await;
+    let reward_mint = env.reward_mint;
+    env.release_vested(&staker, reward_mint).await.unwrap();
+
+    let second_vested = vesting.tranches[1].vested(first_end).unwrap();
+    assert!(second_vested < 10 * REWARD_RATE);
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        10 * REWARD_RATE + second_vested
+    );
+    let vesting: Vesting = env.account(env.vesting(&staker.pubkey())).await;
+    assert_eq!(vesting.tranches.len(), 1);
+}
+
+#[tokio::test]
+async fn release_vested_with_wrong_reward_mint_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    env.set_vesting_schedule(0, 3_600).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.warp_slots(10).await;
+    env.claim(&staker, &staker.pubkey(), true, vec![])
+        .await
+        .unwrap();
+
+    let stake_mint = env.stake_mint;
+    let result = env.release_vested(&staker, stake_mint).await;
+    assert_error(result, MyErrorCode::InvalidRewardMintAccount);
+}
+
+#[tokio::test]
+async fn update_voter_weight_record_without_governance_fails() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let result = env.update_voter_weight_record(&staker).await;
+    assert_error(result, MyErrorCode::GovernanceNotConfigured);
+}
+
+#[tokio::test]
+async fn voted_stake_is_locked_until_the_vote_is_over() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let realm = Pubkey::new_unique();
+    let vote_lock_period = 3_600;
+
+    // without a lock the same stake could vote again from another wallet
+    let result = env.set_governance(realm, 0).await;
+    assert_error(result, MyErrorCode::InvalidVoteLockPeriod);
+    env.set_governance(realm, vote_lock_period).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    env.update_voter_weight_record(&staker).await.unwrap();
+    let record_address = pda(&[
+        VOTER_WEIGHT_SEED,
+        env.pool().as_ref(),
+        staker.pubkey().as_ref(),
+    ]);
+    let record: VoterWeightRecord = env.account(record_address).await;
+    assert_eq!(record.voter_weight, STAKE_AMOUNT);
+
+    // the stake can't leave the pool, immediately or through a ticket
+    let result = env.unstake(&staker, STAKE_AMOUNT, vec![]).await;
+    assert_error(result, MyErrorCode::PositionLockedForVote);
+    env.set_unbonding_period(60).await.unwrap();
+    let result = env.request_unstake(&staker, 0, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::PositionLockedForVote);
+
+    // refreshing the weight later extends the lock
+    env.advance_time(vote_lock_period as i64 / 2).await;
+    env.update_voter_weight_record(&staker).await.unwrap();
+    env.advance_time(vote_lock_period as i64 / 2).await;
+    let result = env.request_unstake(&staker, 0, STAKE_AMOUNT).await;
+    assert_error(result, MyErrorCode::PositionLockedForVote);
+
+    env.advance_time(vote_lock_period as i64 / 2).await;
+    env.request_unstake(&staker, 0, STAKE_AMOUNT).await.unwrap();
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.amount, 0);
+}
+
+#[tokio::test]
+async fn extra_reward_mint_is_added_once() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+    let extra_mint = env.add_extra_reward(2 * REWARD_RATE).await.unwrap();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let slots_passed = 20;
+    env.warp_slots(slots_passed).await;
+
+    // the mint already has a reward_ata in the pool, the duplicate is reported before its init fails
+    let result = env.add_extra_reward_mint(extra_mint, REWARD_RATE).await;
+    assert_error(result, MyErrorCode::DuplicateRewardMint);
+    let pool = env.pool_account().await;
+    assert_eq!(pool.extra_rewards.len(), 1);
+    assert_eq!(pool.extra_rewards[0].allocation, ALLOCATION);
+    assert_eq!(pool.extra_rewards[0].reward_rate, 2 * REWARD_RATE);
+
+    // the extra reward keeps emitting at its own rate
+    let accounts = env
+        .extra_reward_accounts(&staker.pubkey(), &extra_mint)
+        .await;
+    env.claim(&staker, &staker.pubkey(), false, accounts)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &extra_mint)).await,
+        slots_passed * 2 * REWARD_RATE
+    );
+}
+
+#[tokio::test]
+async fn tokens_sent_to_the_pool_are_not_allocated() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let extra_mint = env.create_mint(&[]).await;
+    // a donation creates the reward_ata of the pool before the extra reward is added
+    let pool = env.pool();
+    env.mint_to(&extra_mint, &pool, ONE_TOKEN).await;
+
+    env.add_extra_reward_mint(extra_mint, REWARD_RATE)
+        .await
+        .unwrap();
+    let pool_account = env.pool_account().await;
+    assert_eq!(pool_account.extra_rewards[0].allocation, ALLOCATION);
+    assert_eq!(
+        env.token_balance(ata(&pool, &extra_mint)).await,
+        ALLOCATION + ONE_TOKEN
+    );
+}
+
+#[tokio::test]
+async fn receipt_mint_is_created_once() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+
+    env.create_receipt_mint(ReceiptMode::NonTransferable)
+        .await
+        .unwrap();
+    // the receipt mode can't be switched once receipts may have been minted
+    let result = env.create_receipt_mint(ReceiptMode::Liquid).await;
+    assert_error(result, MyErrorCode::ReceiptMintAlreadySet);
+
+    let pool = env.pool_account().await;
+    assert_eq!(pool.receipt_mode, ReceiptMode::NonTransferable);
+    assert_eq!(pool.receipt_mint, pda(&[RECEIPT_SEED, env.pool().as_ref()]));
+}
+
+#[tokio::test]
+async fn reward_short_of_the_owed_amount_is_not_paid() {
+    let Some(mut env) = TestEnv::new().await else {
+        return;
+    };
+    let staker = env.staker.insecure_clone();
+
+    env.stake(&staker, STAKE_AMOUNT).await.unwrap();
+    let slots_passed = 30;
+    env.warp_slots(slots_passed).await;
+
+    // the reward_ata holds less than the reward of the staker
+    let reward_ata = ata(&env.pool(), &env.reward_mint);
+    let reward = slots_passed * REWARD_RATE;
+    env.set_token_balance(reward_ata, reward - 1).await;
+    let result = env.claim(&staker, &staker.pubkey(), false, vec![]).await;
+    assert_error(result, MyErrorCode::InsufficientRewardBalance);
+    let result = env.unstake(&staker, STAKE_AMOUNT, vec![]).await;
+    assert_error(result, MyErrorCode::InsufficientRewardBalance);
+
+    // nothing was settled, the whole reward is paid once the reward_ata holds it again
+    let stake_info: StakeInfo = env.account(env.stake_info(&staker.pubkey())).await;
+    assert_eq!(stake_info.reward, 0);
+    assert_eq!(stake_info.amount, STAKE_AMOUNT);
+    env.set_token_balance(reward_ata, reward).await;
+    env.claim(&staker, &staker.pubkey(), false, vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(ata(&staker.pubkey(), &env.reward_mint))
+            .await,
+        reward
+    );
+    assert_eq!(env.token_balance(reward_ata).await, 0);
+}
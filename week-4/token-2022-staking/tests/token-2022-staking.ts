@@ -4,14 +4,20 @@ import { AnchorError, Program } from "@coral-xyz/anchor";
 
 import {
   createAssociatedTokenAccountInstruction,
+  createMint,
   createInitializeMetadataPointerInstruction,
   createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
   createMintToCheckedInstruction,
   ExtensionType,
   getAccount,
   getAssociatedTokenAddressSync,
   getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  getTokenMetadata,
   LENGTH_SIZE,
+  mintTo,
   TOKEN_2022_PROGRAM_ID,
   TYPE_SIZE,
 } from "@solana/spl-token";
@@ -33,11 +39,14 @@ describe("token-2022-staking", () => {
   const program = anchor.workspace
     .Token2022Staking as Program<Token2022Staking>;
 
-  // get address of config account
-  const [config] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("config")],
-    program.programId
-  );
+  // get address of a config account from its id
+  const configAddress = (id: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const configId = 0;
+  const config = configAddress(configId);
 
   // create a new mint for reward token
   const rewardMintKeypair = anchor.web3.Keypair.generate();
@@ -67,7 +76,11 @@ describe("token-2022-staking", () => {
 
   // get pool address from stake token
   const [pool] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("pool"), stakeMintKeypair.publicKey.toBuffer()],
+    [
+      Buffer.from("pool"),
+      config.toBuffer(),
+      stakeMintKeypair.publicKey.toBuffer(),
+    ],
     program.programId
   );
 
@@ -93,6 +106,41 @@ describe("token-2022-staking", () => {
     TOKEN_2022_PROGRAM_ID
   );
 
+  // partner token distributed by the pool as an extra reward
+  let partnerMint: anchor.web3.PublicKey;
+  let partnerRewardAta: anchor.web3.PublicKey;
+  let funderPartnerAta: anchor.web3.PublicKey;
+  let stakerPartnerAta: anchor.web3.PublicKey;
+  const partnerAllocation = 1_000_000_000;
+
+  // remaining accounts of an extra reward: mint, pool reward ata, recipient token account, token program
+  const extraRewardAccounts = (recipientAta: anchor.web3.PublicKey) => [
+    { pubkey: partnerMint, isSigner: false, isWritable: false },
+    { pubkey: partnerRewardAta, isSigner: false, isWritable: true },
+    { pubkey: recipientAta, isSigner: false, isWritable: true },
+    { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
+  // soulbound receipt minted to the staker for every staked token
+  const [receiptMint] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), pool.toBuffer()],
+    program.programId
+  );
+  const stakerReceiptAta = getAssociatedTokenAddressSync(
+    receiptMint,
+    staker.publicKey,
+    false,
+    TOKEN_2022_PROGRAM_ID
+  );
+  const receiptAccounts = {
+    receiptMint,
+    stakerReceiptAta,
+    receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+  };
+
+  // the pools of these tests pay the reward immediately
+  const noVesting = { vesting: null, vestingAta: null };
+
   const stakeAmount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
   const unstakeAmount = new anchor.BN(5 * anchor.web3.LAMPORTS_PER_SOL);
   before("Prepare test", async () => {
@@ -178,7 +226,14 @@ describe("token-2022-staking", () => {
 
   it("Is configured", async () => {
     const tx = await program.methods
-      .initialize()
+      .initialize(
+        new anchor.BN(configId),
+        "Solana Bootcamp Token",
+        "SBT",
+        "https://raw.githubusercontent.com/HongThaiPham/solana-bootcamp-autumn-2024/main/week-4/token-2022-staking/app/assets/token-info.json",
+        9,
+        [{ key: "program", value: "token-2022-staking" }]
+      )
       .accountsPartial({
         mint: rewardMintKeypair.publicKey,
         signer: provider.publicKey,
@@ -193,20 +248,73 @@ describe("token-2022-staking", () => {
     const configAccount = await program.account.config.fetch(config);
     assert(configAccount.rewardMint.equals(rewardMintKeypair.publicKey));
     assert(configAccount.authority.equals(provider.publicKey));
+
+    const rewardMetadata = await getTokenMetadata(
+      provider.connection,
+      rewardMintKeypair.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(rewardMetadata.name, "Solana Bootcamp Token");
+    assert.equal(rewardMetadata.symbol, "SBT");
+    assert.deepEqual(rewardMetadata.additionalMetadata, [
+      ["program", "token-2022-staking"],
+    ]);
+  });
+
+  it("Should update reward metadata as config authority", async () => {
+    await program.methods
+      .updateRewardMetadata("symbol", "SBTR")
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        rewardMint: rewardMintKeypair.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .updateRewardMetadata("website", "https://solana.com")
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        rewardMint: rewardMintKeypair.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const rewardMetadata = await getTokenMetadata(
+      provider.connection,
+      rewardMintKeypair.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(rewardMetadata.symbol, "SBTR");
+    assert.deepEqual(rewardMetadata.additionalMetadata, [
+      ["program", "token-2022-staking"],
+      ["website", "https://solana.com"],
+    ]);
   });
 
   it("Should create a new pool", async () => {
+    const endSlot = new anchor.BN(
+      (await provider.connection.getSlot()) + 1_000_000
+    );
     const tx = await program.methods
       .createPool(
         new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
-        rewardPerSlot
+        rewardPerSlot,
+        { slot: {} },
+        endSlot
       )
-      .accounts({
+      .accountsPartial({
         signer: provider.publicKey,
+        config,
         rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
         stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
         rewardMint: rewardMintKeypair.publicKey,
         stakeMint: stakeMintKeypair.publicKey,
+        funderRewardAta: null,
       })
       .rpc();
     console.log("Your transaction signature", tx);
@@ -214,7 +322,11 @@ describe("token-2022-staking", () => {
     const poolAccount = await program.account.pool.fetch(pool);
     assert(poolAccount.rewardMint.equals(rewardMintKeypair.publicKey));
     assert(poolAccount.stakeMint.equals(stakeMintKeypair.publicKey));
-    assert(poolAccount.rewardPerSlot.eq(rewardPerSlot));
+    assert(poolAccount.rewardRate.eq(rewardPerSlot));
+    assert.deepEqual(poolAccount.emissionMode, { slot: {} });
+    assert.deepEqual(poolAccount.rewardSource, { minted: {} });
+    assert(poolAccount.config.equals(config));
+    assert(poolAccount.emissionEnd.eq(endSlot));
     assert(
       poolAccount.allocation.eq(
         new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL)
@@ -235,13 +347,196 @@ describe("token-2022-staking", () => {
     );
   });
 
+  it("Should update pool as config authority", async () => {
+    const newRewardPerSlot = rewardPerSlot.muln(2);
+    await program.methods
+      .setRewardRate(newRewardPerSlot)
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+      })
+      .rpc();
+
+    const topUpAmount = new anchor.BN(100 * anchor.web3.LAMPORTS_PER_SOL);
+    await program.methods
+      .topUp(topUpAmount)
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+        rewardMint: rewardMintKeypair.publicKey,
+        rewardAta,
+        funderRewardAta: null,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert(poolAccount.rewardRate.eq(newRewardPerSlot));
+    assert(
+      poolAccount.allocation.eq(
+        new anchor.BN(1100 * anchor.web3.LAMPORTS_PER_SOL)
+      )
+    );
+
+    const rewardAtaAccount = await getAccount(
+      provider.connection,
+      rewardAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert(
+      new anchor.BN(rewardAtaAccount.amount.toString()).eq(
+        new anchor.BN(1100 * anchor.web3.LAMPORTS_PER_SOL)
+      )
+    );
+  });
+
+  it("Should add a partner token as extra reward", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    partnerMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    funderPartnerAta = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        payer,
+        partnerMint,
+        provider.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    stakerPartnerAta = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        payer,
+        partnerMint,
+        staker.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      payer,
+      partnerMint,
+      funderPartnerAta,
+      payer,
+      partnerAllocation,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    partnerRewardAta = getAssociatedTokenAddressSync(
+      partnerMint,
+      pool,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .addExtraReward(new anchor.BN(partnerAllocation), new anchor.BN(1_000))
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+        rewardMint: partnerMint,
+        rewardAta: partnerRewardAta,
+        funderRewardAta: funderPartnerAta,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert.equal(poolAccount.extraRewards.length, 1);
+    assert(poolAccount.extraRewards[0].mint.equals(partnerMint));
+    assert(
+      poolAccount.extraRewards[0].allocation.eq(
+        new anchor.BN(partnerAllocation)
+      )
+    );
+  });
+
+  it("Should create a soulbound receipt mint for the pool", async () => {
+    await program.methods
+      .createReceiptMint({ nonTransferable: {} })
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+        stakeMint: stakeMintKeypair.publicKey,
+        receiptMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert.deepEqual(poolAccount.receiptMode, { nonTransferable: {} });
+    assert(poolAccount.receiptMint.equals(receiptMint));
+  });
+
+  it("Should reject stake while pool is paused", async () => {
+    await program.methods
+      .setPoolPaused(true)
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .stake(stakeAmount)
+        .accounts({
+          signer: staker.publicKey,
+          stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+          pool,
+        })
+        .signers([staker])
+        .rpc();
+      assert.ok(false);
+    } catch (error) {
+      assert.isTrue(error instanceof AnchorError);
+      const err: AnchorError = error;
+      assert.equal(err.error.errorCode.code, "PoolPaused");
+    }
+
+    await program.methods
+      .setPoolPaused(false)
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert.isFalse(poolAccount.paused);
+  });
+
   it("Should stake successfully", async () => {
     const tx = await program.methods
       .stake(stakeAmount)
-      .accounts({
+      .accountsPartial({
         signer: staker.publicKey,
         stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
         pool,
+        stakeMint: stakeMintKeypair.publicKey,
+        ...receiptAccounts,
       })
       .signers([staker])
       .rpc();
@@ -263,6 +558,161 @@ describe("token-2022-staking", () => {
     assert(
       new anchor.BN(stakeInfoAtaAccount.amount.toString()).eq(stakeAmount)
     );
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert(poolAccount.totalStaked.eq(stakeAmount));
+
+    const receiptAccount = await getAccount(
+      provider.connection,
+      stakerReceiptAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(receiptAccount.amount.toString(), stakeAmount.toString());
+
+    // the Staked event is the data of a self-CPI, after the 8 bytes of the event instruction tag
+    const txDetails = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = txDetails.meta.innerInstructions
+      .flatMap((inner) => inner.instructions)
+      .filter(
+        (ix) =>
+          txDetails.transaction.message.staticAccountKeys[ix.programIdIndex].equals(
+            program.programId
+          )
+      )
+      .map((ix) =>
+        program.coder.events.decode(
+          anchor.utils.bytes.base64.encode(
+            anchor.utils.bytes.bs58.decode(ix.data).subarray(8)
+          )
+        )
+      );
+    const staked = events.find((event) => event?.name === "staked");
+    assert.isDefined(staked);
+    assert(staked.data.staker.equals(staker.publicKey));
+    assert(staked.data.amount.eq(stakeAmount));
+  });
+
+  it("Should refresh the voter weight record of a staker", async () => {
+    // a realm using this program as its voter weight addin
+    const realm = anchor.web3.Keypair.generate().publicKey;
+    const governingTokenMint = anchor.web3.Keypair.generate().publicKey;
+    await program.methods
+      .setGovernance(realm, governingTokenMint, new anchor.BN(1))
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+      })
+      .rpc();
+
+    const [voterWeightRecord] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("voter-weight-record"),
+        pool.toBuffer(),
+        staker.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .updateVoterWeightRecord()
+      .accountsPartial({
+        signer: staker.publicKey,
+        pool,
+        stakeInfo,
+        voterWeightRecord,
+      })
+      .signers([staker])
+      .rpc();
+
+    // no unbonding period, the weight is the staked amount
+    const record = await program.account.voterWeightRecord.fetch(
+      voterWeightRecord
+    );
+    assert(record.realm.equals(realm));
+    assert(record.governingTokenMint.equals(governingTokenMint));
+    assert(record.governingTokenOwner.equals(staker.publicKey));
+    assert(record.voterWeight.eq(stakeAmount));
+    assert.isNotNull(record.voterWeightExpiry);
+
+    // the stake stays in the pool for the vote lock period of one second
+    const locked = await program.account.stakeInfo.fetch(stakeInfo);
+    assert(locked.voteLockedUntil.gt(new anchor.BN(0)));
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  });
+
+  it("Should read pending reward and pool info through views", async () => {
+    const pending = await program.methods
+      .pendingReward()
+      .accountsPartial({ pool, staker: staker.publicKey, stakeInfo })
+      .view();
+    assert(pending.staked.eq(stakeAmount));
+    assert(pending.reward.gt(new anchor.BN(0)));
+    assert.equal(pending.extraRewards.length, 1);
+
+    const info = await program.methods.poolInfo().accounts({ pool }).view();
+    assert(info.stakeMint.equals(stakeMintKeypair.publicKey));
+    assert(info.totalStaked.eq(stakeAmount));
+    assert(info.stakerCount.eq(new anchor.BN(1)));
+    assert(info.rewardRate.eq(rewardPerSlot));
+    assert.deepEqual(info.emissionMode, { slot: {} });
+    assert(info.extraRewards[0].mint.equals(partnerMint));
+  });
+
+  it("Should claim reward without unstaking", async () => {
+    const tx = await program.methods
+      .claim()
+      .accountsPartial({
+        rewardMint: rewardMintKeypair.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeMint: stakeMintKeypair.publicKey,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        signer: staker.publicKey,
+        staker: staker.publicKey,
+        pool,
+        ...noVesting,
+      })
+      .remainingAccounts(extraRewardAccounts(stakerPartnerAta))
+      .signers([staker])
+      .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    const stakeInfoAccount = await program.account.stakeInfo.fetch(stakeInfo);
+
+    assert(stakeInfoAccount.amount.eq(stakeAmount));
+    assert(stakeInfoAccount.reward.eq(new anchor.BN(0)));
+
+    const stakerPartnerAccount = await getAccount(
+      provider.connection,
+      stakerPartnerAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert(Number(stakerPartnerAccount.amount) > 0);
+
+    const stakerRewardAta = getAssociatedTokenAddressSync(
+      rewardMintKeypair.publicKey,
+      staker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const stakerRewardAtaAccount = await getAccount(
+      provider.connection,
+      stakerRewardAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    assert(
+      new anchor.BN(stakerRewardAtaAccount.amount.toString()).gt(
+        new anchor.BN(0)
+      )
+    );
   });
 
   it("Should unstake successfully", async () => {
@@ -274,7 +724,11 @@ describe("token-2022-staking", () => {
         stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
         signer: staker.publicKey,
         stakeMint: stakeMintKeypair.publicKey,
+        pool,
+        ...receiptAccounts,
+        ...noVesting,
       })
+      .remainingAccounts(extraRewardAccounts(stakerPartnerAta))
       .signers([staker])
       .rpc();
 
@@ -290,6 +744,18 @@ describe("token-2022-staking", () => {
 
     assert(stakeInfoAccount.reward.eq(new anchor.BN(0)));
 
+    // the receipts of the unstaked amount were burned
+    const receiptAccount = await getAccount(
+      provider.connection,
+      stakerReceiptAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(
+      receiptAccount.amount.toString(),
+      stakeAmount.sub(unstakeAmount).toString()
+    );
+
     const stakerRewardAta = getAssociatedTokenAddressSync(
       rewardMintKeypair.publicKey,
       staker.publicKey,
@@ -320,7 +786,11 @@ describe("token-2022-staking", () => {
         stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
         signer: staker.publicKey,
         stakeMint: stakeMintKeypair.publicKey,
+        pool,
+        ...receiptAccounts,
+        ...noVesting,
       })
+      .remainingAccounts(extraRewardAccounts(stakerPartnerAta))
       .signers([staker])
       .rpc();
 
@@ -355,4 +825,773 @@ describe("token-2022-staking", () => {
       )
     );
   });
+
+  it("Should close the empty pool", async () => {
+    const tx = await program.methods
+      .closePool()
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool,
+        rewardMint: rewardMintKeypair.publicKey,
+        rewardAta,
+        funderRewardAta: null,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(extraRewardAccounts(funderPartnerAta))
+      .rpc();
+
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await program.account.pool.fetchNullable(pool));
+    assert.isNull(await provider.connection.getAccountInfo(rewardAta));
+    assert.isNull(await provider.connection.getAccountInfo(partnerRewardAta));
+
+    // the undistributed partner reward went back to the funder
+    const stakerPartnerAccount = await getAccount(
+      provider.connection,
+      stakerPartnerAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const funderPartnerAccount = await getAccount(
+      provider.connection,
+      funderPartnerAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(
+      Number(funderPartnerAccount.amount) + Number(stakerPartnerAccount.amount),
+      partnerAllocation
+    );
+  });
+
+  it("Should create a pool rewarding a deposited mint in another config", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const otherConfigId = 1;
+    const otherConfig = configAddress(otherConfigId);
+    const otherRewardMintKeypair = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .initialize(
+        new anchor.BN(otherConfigId),
+        "Other Reward Token",
+        "ORT",
+        "",
+        6,
+        []
+      )
+      .accountsPartial({
+        mint: otherRewardMintKeypair.publicKey,
+        signer: provider.publicKey,
+        config: otherConfig,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([otherRewardMintKeypair])
+      .rpc();
+
+    // a mint created outside of the program, the pool is funded by deposit
+    const externalMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const funderRewardAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      externalMint,
+      provider.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const deposit = 1_000_000_000;
+    await mintTo(
+      provider.connection,
+      payer,
+      externalMint,
+      funderRewardAta.address,
+      payer,
+      deposit,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const [otherPool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        otherConfig.toBuffer(),
+        stakeMintKeypair.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const otherRewardAta = getAssociatedTokenAddressSync(
+      externalMint,
+      otherPool,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .createPool(
+        new anchor.BN(deposit),
+        new anchor.BN(1_000),
+        { slot: {} },
+        new anchor.BN((await provider.connection.getSlot()) + 1_000_000)
+      )
+      .accountsPartial({
+        signer: provider.publicKey,
+        config: otherConfig,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        rewardMint: externalMint,
+        stakeMint: stakeMintKeypair.publicKey,
+        funderRewardAta: funderRewardAta.address,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(otherPool);
+    assert(poolAccount.config.equals(otherConfig));
+    assert(poolAccount.rewardMint.equals(externalMint));
+    assert.deepEqual(poolAccount.rewardSource, { deposited: {} });
+    assert(poolAccount.allocation.eq(new anchor.BN(deposit)));
+
+    // the undistributed deposit goes back to the authority when the pool is closed
+    await program.methods
+      .closePool()
+      .accountsPartial({
+        signer: provider.publicKey,
+        config: otherConfig,
+        pool: otherPool,
+        rewardMint: externalMint,
+        rewardAta: otherRewardAta,
+        funderRewardAta: funderRewardAta.address,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const funderAccount = await getAccount(
+      provider.connection,
+      funderRewardAta.address,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(funderAccount.amount), deposit);
+  });
+
+  // create a Token-2022 stake mint with one extension, owned by the provider
+  const createStakeMintWithExtension = async (
+    extension: ExtensionType,
+    initializeExtension: (
+      mint: anchor.web3.PublicKey
+    ) => anchor.web3.TransactionInstruction
+  ) => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const mintLen = getMintLen([extension]);
+    const transaction = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: provider.publicKey,
+        newAccountPubkey: mintKeypair.publicKey,
+        space: mintLen,
+        lamports: await provider.connection.getMinimumBalanceForRentExemption(
+          mintLen
+        ),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      initializeExtension(mintKeypair.publicKey),
+      createInitializeMintInstruction(
+        mintKeypair.publicKey,
+        9,
+        provider.publicKey,
+        null,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await provider.sendAndConfirm(transaction, [mintKeypair]);
+    return mintKeypair.publicKey;
+  };
+
+  const createPoolFor = (mint: anchor.web3.PublicKey) =>
+    program.methods
+      .createPool(
+        new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
+        rewardPerSlot,
+        { slot: {} },
+        new anchor.BN(1_000_000_000)
+      )
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        rewardMint: rewardMintKeypair.publicKey,
+        stakeMint: mint,
+        funderRewardAta: null,
+      })
+      .rpc();
+
+  it("Should reject a non-transferable stake mint", async () => {
+    const nonTransferableMint = await createStakeMintWithExtension(
+      ExtensionType.NonTransferable,
+      (mint) =>
+        createInitializeNonTransferableMintInstruction(
+          mint,
+          TOKEN_2022_PROGRAM_ID
+        )
+    );
+
+    try {
+      await createPoolFor(nonTransferableMint);
+      assert.ok(false);
+    } catch (error) {
+      assert.isTrue(error instanceof AnchorError);
+      const err: AnchorError = error;
+      assert.equal(err.error.errorCode.code, "UnsupportedStakeMintExtension");
+    }
+  });
+
+  it("Should credit the stake net of transfer fees", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    // 1% transfer fee
+    const feeMint = await createStakeMintWithExtension(
+      ExtensionType.TransferFeeConfig,
+      (mint) =>
+        createInitializeTransferFeeConfigInstruction(
+          mint,
+          provider.publicKey,
+          provider.publicKey,
+          100,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        )
+    );
+    await createPoolFor(feeMint);
+
+    const stakerFeeAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      feeMint,
+      staker.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      feeMint,
+      stakerFeeAta.address,
+      payer,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const [feePool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), feeMint.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .stake(new anchor.BN(1_000_000))
+      .accounts({
+        signer: staker.publicKey,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        pool: feePool,
+      })
+      .signers([staker])
+      .rpc();
+
+    const [feeStakeInfo] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stakeinfo"), feePool.toBuffer(), staker.publicKey.toBuffer()],
+      program.programId
+    );
+    const stakeInfoAccount = await program.account.stakeInfo.fetch(
+      feeStakeInfo
+    );
+    const vault = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(feeMint, feeStakeInfo, true, TOKEN_2022_PROGRAM_ID),
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert(stakeInfoAccount.amount.eq(new anchor.BN(990_000)));
+    assert.equal(Number(vault.amount), 990_000);
+  });
+
+  it("Should unbond through tickets when the pool has a cooldown", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const unbondingMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      null,
+      9,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await createPoolFor(unbondingMint);
+    const [unbondingPool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), unbondingMint.toBuffer()],
+      program.programId
+    );
+
+    const stakerUnbondingAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      unbondingMint,
+      staker.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      unbondingMint,
+      stakerUnbondingAta.address,
+      payer,
+      2_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .stake(new anchor.BN(2_000_000))
+      .accounts({
+        signer: staker.publicKey,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        pool: unbondingPool,
+      })
+      .signers([staker])
+      .rpc();
+
+    const setUnbondingPeriod = (seconds: number) =>
+      program.methods
+        .setUnbondingPeriod(new anchor.BN(seconds))
+        .accountsPartial({
+          signer: provider.publicKey,
+          config,
+          pool: unbondingPool,
+        })
+        .rpc();
+    const ticketAddress = (id: number) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("unbonding"),
+          unbondingPool.toBuffer(),
+          staker.publicKey.toBuffer(),
+          new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const requestUnstake = (id: number, amount: number) =>
+      program.methods
+        .requestUnstake(new anchor.BN(id), new anchor.BN(amount))
+        .accountsPartial({
+          signer: staker.publicKey,
+          pool: unbondingPool,
+          stakeMint: unbondingMint,
+          ticket: ticketAddress(id),
+          receiptMint: null,
+          stakerReceiptAta: null,
+          receiptTokenProgram: null,
+          stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+    const unbondingStakeInfo = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stakeinfo"), unbondingPool.toBuffer(), staker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const withdrawUnbonded = (id: number) =>
+      program.methods
+        .withdrawUnbonded()
+        .accountsPartial({
+          signer: staker.publicKey,
+          ticket: ticketAddress(id),
+          stakeInfo: unbondingStakeInfo,
+          stakeMint: unbondingMint,
+          stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+
+    // one hour cooldown, the first ticket stays locked
+    await setUnbondingPeriod(3600);
+    await requestUnstake(0, 1_000_000);
+    const ticket = await program.account.unbondingTicket.fetch(ticketAddress(0));
+    assert(ticket.amount.eq(new anchor.BN(1_000_000)));
+    assert((await program.account.pool.fetch(unbondingPool)).totalStaked.eq(new anchor.BN(1_000_000)));
+
+    try {
+      await withdrawUnbonded(0);
+      assert.ok(false);
+    } catch (error) {
+      assert.isTrue(error instanceof AnchorError);
+      const err: AnchorError = error;
+      assert.equal(err.error.errorCode.code, "UnbondingNotFinished");
+    }
+
+    // without cooldown, a second ticket can be withdrawn right away
+    await setUnbondingPeriod(0);
+    await requestUnstake(1, 1_000_000);
+    await withdrawUnbonded(1);
+
+    const stakerAccount = await getAccount(
+      provider.connection,
+      stakerUnbondingAta.address,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(stakerAccount.amount), 1_000_000);
+    assert.isNull(await provider.connection.getAccountInfo(ticketAddress(1)));
+    // the first ticket keeps the position open until it is withdrawn
+    const stakeInfoAccount = await program.account.stakeInfo.fetch(unbondingStakeInfo);
+    assert(stakeInfoAccount.amount.eq(new anchor.BN(0)));
+    assert(stakeInfoAccount.unbondingCount.eq(new anchor.BN(1)));
+  });
+
+  it("Should stake for a beneficiary and claim through a delegate", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    // the provider is the treasury, the staker is the employee
+    const payrollMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      null,
+      9,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await createPoolFor(payrollMint);
+    const [payrollPool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), payrollMint.toBuffer()],
+      program.programId
+    );
+    const treasuryAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      payrollMint,
+      provider.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      payrollMint,
+      treasuryAta.address,
+      payer,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .stakeFor(new anchor.BN(1_000_000))
+      .accountsPartial({
+        signer: provider.publicKey,
+        beneficiary: staker.publicKey,
+        pool: payrollPool,
+        stakeMint: payrollMint,
+        signerAta: treasuryAta.address,
+        receiptMint: null,
+        beneficiaryReceiptAta: null,
+        receiptTokenProgram: null,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const [payrollStakeInfo] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stakeinfo"), payrollPool.toBuffer(), staker.publicKey.toBuffer()],
+      program.programId
+    );
+    let stakeInfoAccount = await program.account.stakeInfo.fetch(payrollStakeInfo);
+    assert(stakeInfoAccount.staker.equals(staker.publicKey));
+    assert(stakeInfoAccount.amount.eq(new anchor.BN(1_000_000)));
+
+    const claimAsProvider = () =>
+      program.methods
+        .claim()
+        .accountsPartial({
+          signer: provider.publicKey,
+          staker: staker.publicKey,
+          pool: payrollPool,
+          rewardMint: rewardMintKeypair.publicKey,
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+          stakeMint: payrollMint,
+          stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+          ...noVesting,
+        })
+        .rpc();
+
+    // the treasury can't claim until the employee makes it its delegate
+    try {
+      await claimAsProvider();
+      assert.ok(false);
+    } catch (error) {
+      assert.isTrue(error instanceof AnchorError);
+      const err: AnchorError = error;
+      assert.equal(err.error.errorCode.code, "InvalidClaimAuthority");
+    }
+
+    await program.methods
+      .setClaimDelegate(provider.publicKey)
+      .accountsPartial({
+        signer: staker.publicKey,
+        pool: payrollPool,
+      })
+      .signers([staker])
+      .rpc();
+    stakeInfoAccount = await program.account.stakeInfo.fetch(payrollStakeInfo);
+    assert(stakeInfoAccount.claimDelegate.equals(provider.publicKey));
+
+    // the reward is harvested by the delegate into the staker reward account
+    const stakerRewardAta = getAssociatedTokenAddressSync(
+      rewardMintKeypair.publicKey,
+      staker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const balanceBefore = Number(
+      (await getAccount(provider.connection, stakerRewardAta, null, TOKEN_2022_PROGRAM_ID)).amount
+    );
+    await claimAsProvider();
+    const balanceAfter = Number(
+      (await getAccount(provider.connection, stakerRewardAta, null, TOKEN_2022_PROGRAM_ID)).amount
+    );
+    assert.isAbove(balanceAfter, balanceBefore);
+  });
+
+  it("Should vest the claimed reward and release it", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const vestingMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      null,
+      9,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await createPoolFor(vestingMint);
+    const [vestingPool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), vestingMint.toBuffer()],
+      program.programId
+    );
+    // no cliff, everything vested after 2 seconds
+    await program.methods
+      .setVestingSchedule(new anchor.BN(0), new anchor.BN(2))
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool: vestingPool,
+      })
+      .rpc();
+
+    const treasuryAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      vestingMint,
+      provider.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      vestingMint,
+      treasuryAta.address,
+      payer,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .stakeFor(new anchor.BN(1_000_000))
+      .accountsPartial({
+        signer: provider.publicKey,
+        beneficiary: staker.publicKey,
+        pool: vestingPool,
+        stakeMint: vestingMint,
+        signerAta: treasuryAta.address,
+        receiptMint: null,
+        beneficiaryReceiptAta: null,
+        receiptTokenProgram: null,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const [vesting] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vesting"), vestingPool.toBuffer(), staker.publicKey.toBuffer()],
+      program.programId
+    );
+    const vestingAta = getAssociatedTokenAddressSync(
+      rewardMintKeypair.publicKey,
+      vesting,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // the claimed reward goes to the vesting account
+    await program.methods
+      .claim()
+      .accountsPartial({
+        signer: staker.publicKey,
+        staker: staker.publicKey,
+        pool: vestingPool,
+        rewardMint: rewardMintKeypair.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeMint: vestingMint,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        vesting,
+        vestingAta,
+      })
+      .signers([staker])
+      .rpc();
+
+    const vestingAccount = await program.account.vesting.fetch(vesting);
+    assert.equal(vestingAccount.tranches.length, 1);
+    assert(vestingAccount.tranches[0].total.gt(new anchor.BN(0)));
+    assert(vestingAccount.tranches[0].duration.eq(new anchor.BN(2)));
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    await program.methods
+      .releaseVested()
+      .accountsPartial({
+        signer: staker.publicKey,
+        vesting,
+        rewardMint: rewardMintKeypair.publicKey,
+        vestingAta,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    const vestingAtaAccount = await getAccount(
+      provider.connection,
+      vestingAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(vestingAtaAccount.amount), 0);
+  });
+
+  it("Should compound the reward into liquid staking shares", async () => {
+    // the reward mint is staked, so the reward compounds into the pool
+    const liquidMint = rewardMintKeypair.publicKey;
+    await createPoolFor(liquidMint);
+
+    const [liquidPool] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), config.toBuffer(), liquidMint.toBuffer()],
+      program.programId
+    );
+    const [shareMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("share"), liquidPool.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .enableLiquidStaking()
+      .accountsPartial({
+        signer: provider.publicKey,
+        config,
+        pool: liquidPool,
+        stakeMint: liquidMint,
+        shareMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolAccount = await program.account.pool.fetch(liquidPool);
+    assert.deepEqual(poolAccount.stakeMode, { liquid: {} });
+    assert(poolAccount.shareMint.equals(shareMint));
+
+    // the staker stakes part of the reward claimed earlier
+    const stakerLiquidAta = getAssociatedTokenAddressSync(
+      liquidMint,
+      staker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const stakerShareAta = getAssociatedTokenAddressSync(
+      shareMint,
+      staker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const amount = 1_000_000;
+    const balanceBefore = Number(
+      (await getAccount(provider.connection, stakerLiquidAta, null, TOKEN_2022_PROGRAM_ID)).amount
+    );
+
+    await program.methods
+      .liquidStake(new anchor.BN(amount))
+      .accountsPartial({
+        signer: staker.publicKey,
+        pool: liquidPool,
+        stakeMint: liquidMint,
+        shareMint,
+        stakerAta: stakerLiquidAta,
+        rewardAta: poolAccount.rewardAta,
+        stakerShareAta,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    // the first staker gets one share per staked token
+    const shareAccount = await getAccount(
+      provider.connection,
+      stakerShareAta,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(shareAccount.amount), amount);
+
+    // let some slots pass, the shares are worth the stake and the reward of those slots
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    await program.methods
+      .liquidUnstake(new anchor.BN(amount))
+      .accountsPartial({
+        signer: staker.publicKey,
+        pool: liquidPool,
+        stakeMint: liquidMint,
+        shareMint,
+        stakerAta: stakerLiquidAta,
+        rewardAta: poolAccount.rewardAta,
+        stakerShareAta,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    const balanceAfter = Number(
+      (await getAccount(provider.connection, stakerLiquidAta, null, TOKEN_2022_PROGRAM_ID)).amount
+    );
+    assert.isAbove(balanceAfter, balanceBefore);
+    assert((await program.account.pool.fetch(liquidPool)).totalStaked.isZero());
+  });
 });
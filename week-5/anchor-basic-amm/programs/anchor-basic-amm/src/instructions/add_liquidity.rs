@@ -2,13 +2,15 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::Token,
-    token_interface::{
-        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
-    },
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
 use spl_math::uint::U256;
 
-use crate::{error::AmmErrorCode, utils::calculate_desired_amount_deposit, Pool, POOL_SEED};
+use crate::{
+    error::AmmErrorCode,
+    utils::{calculate_desired_amount_deposit, transfer_checked_with_hook},
+    Pool, POOL_SEED,
+};
 
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
@@ -79,7 +81,13 @@ pub struct AddLiquidity<'info> {
 }
 
 impl<'info> AddLiquidity<'info> {
-    pub fn handler(&mut self, max_amount_x: u64, max_amount_y: u64) -> Result<()> {
+    // remaining_accounts are the transfer hook accounts of mint X and mint Y, if any
+    pub fn handler(
+        &mut self,
+        max_amount_x: u64,
+        max_amount_y: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let user_balance_x = self.user_x_ata.amount;
         let user_balance_y = self.user_y_ata.amount;
 
@@ -94,7 +102,7 @@ impl<'info> AddLiquidity<'info> {
             AmmErrorCode::InsufficientBalance
         );
 
-        let (amount_x, amount_y) = self.despoit(max_amount_x, max_amount_y)?;
+        let (amount_x, amount_y) = self.despoit(max_amount_x, max_amount_y, remaining_accounts)?;
 
         self.mint_lp(amount_x, amount_y)?;
 
@@ -149,7 +157,12 @@ impl<'info> AddLiquidity<'info> {
         Ok(())
     }
 
-    fn despoit(&mut self, max_amount_x: u64, max_amount_y: u64) -> Result<(u64, u64)> {
+    fn despoit(
+        &mut self,
+        max_amount_x: u64,
+        max_amount_y: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
         let pool_balance_x = self.pool_x_ata.amount;
         let pool_balance_y = self.pool_y_ata.amount;
         let lp_supply = self.mint_lp.supply;
@@ -180,7 +193,7 @@ impl<'info> AddLiquidity<'info> {
             }
         };
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new(
                 self.mint_x_token_program.to_account_info(),
                 TransferChecked {
@@ -189,12 +202,13 @@ impl<'info> AddLiquidity<'info> {
                     to: self.pool_x_ata.to_account_info(),
                     authority: self.signer.to_account_info(),
                 },
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount_x,
             self.mint_x.decimals,
         )?;
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new(
                 self.mint_y_token_program.to_account_info(),
                 TransferChecked {
@@ -203,7 +217,8 @@ impl<'info> AddLiquidity<'info> {
                     to: self.pool_y_ata.to_account_info(),
                     authority: self.signer.to_account_info(),
                 },
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount_y,
             self.mint_y.decimals,
         )?;
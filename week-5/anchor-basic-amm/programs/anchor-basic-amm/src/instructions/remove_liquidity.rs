@@ -3,12 +3,16 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::Token,
     token_interface::{
-        burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount,
-        TokenInterface, TransferChecked,
+        burn, close_account, Burn, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
     },
 };
 
-use crate::{error::AmmErrorCode, utils::calculate_x_y_amount_return, Pool, POOL_SEED};
+use crate::{
+    error::AmmErrorCode,
+    utils::{calculate_x_y_amount_return, transfer_checked_with_hook},
+    Pool, POOL_SEED,
+};
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
@@ -80,7 +84,12 @@ pub struct RemoveLiquidity<'info> {
 
 // dx = X * s / T , dy  = Y * s / T
 impl<'info> RemoveLiquidity<'info> {
-    pub fn handler(&mut self, shares: u64) -> Result<()> {
+    // remaining_accounts are the transfer hook accounts of mint X and mint Y, if any
+    pub fn handler(
+        &mut self,
+        shares: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let x = self.pool_x_ata.amount;
         let y = self.pool_y_ata.amount;
         let t = self.mint_lp.supply;
@@ -90,7 +99,7 @@ impl<'info> RemoveLiquidity<'info> {
         require_gt!(amount_y, 0, AmmErrorCode::ZeroAmount);
 
         self.burn_shares(shares)?;
-        self.transfer_back_to_user(amount_x, amount_y)?;
+        self.transfer_back_to_user(amount_x, amount_y, remaining_accounts)?;
         Ok(())
     }
 
@@ -124,7 +133,12 @@ impl<'info> RemoveLiquidity<'info> {
         Ok(())
     }
 
-    fn transfer_back_to_user(&mut self, amount_x: u64, amount_y: u64) -> Result<()> {
+    fn transfer_back_to_user(
+        &mut self,
+        amount_x: u64,
+        amount_y: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let pool = self.pool.clone();
         let seeds = &[
             POOL_SEED,
@@ -135,7 +149,7 @@ impl<'info> RemoveLiquidity<'info> {
 
         let signer_seeds = &[&seeds[..]];
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new_with_signer(
                 self.mint_x_token_program.to_account_info(),
                 TransferChecked {
@@ -145,12 +159,13 @@ impl<'info> RemoveLiquidity<'info> {
                     authority: self.pool.to_account_info(),
                 },
                 signer_seeds,
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount_x,
             self.mint_x.decimals,
         )?;
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new_with_signer(
                 self.mint_y_token_program.to_account_info(),
                 TransferChecked {
@@ -160,7 +175,8 @@ impl<'info> RemoveLiquidity<'info> {
                     authority: self.pool.to_account_info(),
                 },
                 signer_seeds,
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount_y,
             self.mint_y.decimals,
         )?;
@@ -2,12 +2,13 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::Token,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
-    error::AmmErrorCode, utils::calculate_desired_amount_withdraw, AmmConfig, Pool, CONFIG_SEED,
-    POOL_SEED,
+    error::AmmErrorCode,
+    utils::{calculate_desired_amount_withdraw, transfer_checked_with_hook},
+    AmmConfig, Pool, CONFIG_SEED, POOL_SEED,
 };
 
 #[derive(Accounts)]
@@ -77,6 +78,7 @@ impl<'info> Swap<'info> {
         x_to_y: bool,
         amount_in: u64,
         min_amount_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
         _bumps: &SwapBumps,
     ) -> Result<()> {
         let (x, y) = match x_to_y {
@@ -84,7 +86,8 @@ impl<'info> Swap<'info> {
             false => (self.pool_y_ata.amount, self.pool_x_ata.amount),
         };
 
-        self.deposit(x_to_y, amount_in)?;
+        // remaining_accounts are the transfer hook accounts of mint X and mint Y, if any
+        self.deposit(x_to_y, amount_in, remaining_accounts)?;
 
         let amount_in_minus_fees = amount_in
             .checked_mul(10000_u64.checked_sub(self.config.fee as u64).unwrap())
@@ -97,12 +100,17 @@ impl<'info> Swap<'info> {
 
         require_gte!(amount_out, min_amount_out, AmmErrorCode::InvalidParams);
 
-        self.withdraw(x_to_y, amount_out)?;
+        self.withdraw(x_to_y, amount_out, remaining_accounts)?;
 
         Ok(())
     }
 
-    fn withdraw(&mut self, x_to_y: bool, amount: u64) -> Result<()> {
+    fn withdraw(
+        &mut self,
+        x_to_y: bool,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let pool = self.pool.clone();
         let (mint, decimals, token_program, from, to) = match x_to_y {
             true => (
@@ -130,7 +138,7 @@ impl<'info> Swap<'info> {
 
         let signer_seeds = &[&seeds[..]];
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new_with_signer(
                 token_program,
                 TransferChecked {
@@ -140,14 +148,20 @@ impl<'info> Swap<'info> {
                     authority: self.pool.to_account_info(),
                 },
                 signer_seeds,
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount,
             decimals,
         )?;
         Ok(())
     }
 
-    fn deposit(&mut self, x_to_y: bool, amount_in: u64) -> Result<()> {
+    fn deposit(
+        &mut self,
+        x_to_y: bool,
+        amount_in: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let (deposit_mint, deposit_decimals, deposit_token_program, deposit_from, desposit_to) =
             match x_to_y {
                 true => (
@@ -166,7 +180,7 @@ impl<'info> Swap<'info> {
                 ),
             };
 
-        transfer_checked(
+        transfer_checked_with_hook(
             CpiContext::new(
                 deposit_token_program,
                 TransferChecked {
@@ -175,7 +189,8 @@ impl<'info> Swap<'info> {
                     to: desposit_to,
                     authority: self.signer.to_account_info(),
                 },
-            ),
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
             amount_in,
             deposit_decimals,
         )?;
@@ -24,21 +24,34 @@ pub mod anchor_basic_amm {
         ctx.accounts.handler(&ctx.bumps)
     }
 
-    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_x: u64, amount_y: u64) -> Result<()> {
-        ctx.accounts.handler(amount_x, amount_y)
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddLiquidity<'info>>,
+        amount_x: u64,
+        amount_y: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .handler(amount_x, amount_y, ctx.remaining_accounts)
     }
 
-    pub fn swap(
-        ctx: Context<Swap>,
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
         x_to_y: bool,
         amount_in: u64,
         minimum_amount_out: u64,
     ) -> Result<()> {
-        ctx.accounts
-            .handler(x_to_y, amount_in, minimum_amount_out, &ctx.bumps)
+        ctx.accounts.handler(
+            x_to_y,
+            amount_in,
+            minimum_amount_out,
+            ctx.remaining_accounts,
+            &ctx.bumps,
+        )
     }
 
-    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
-        ctx.accounts.handler(shares)
+    pub fn remove_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveLiquidity<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(shares, ctx.remaining_accounts)
     }
 }
@@ -1,8 +1,34 @@
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::onchain::invoke_transfer_checked, token_interface::TransferChecked,
+};
 use spl_math::uint::U256;
 
 use crate::error::AmmErrorCode;
 
+// transfer_checked forwarding the accounts of a Token-2022 transfer hook from ctx.remaining_accounts
+// (the hook program, its extra-account-metas account and the extra accounts it lists)
+// accounts are found by address, so the same list can serve mint X and mint Y
+// and a mint without transfer hook ignores it
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
 // Calculate the amount of X must deposit to get a of Y
 // dx = X. dy / Y
 pub fn calculate_desired_amount_deposit(x: u64, y: u64, a: u64) -> Result<u64> {